@@ -0,0 +1,94 @@
+use futures::{
+    executor::block_on,
+    future::{join, poll_fn},
+    io::AllowStdIo,
+};
+use rasset::prelude::*;
+use std::{fs::File, task::Poll};
+
+asset_def! {
+    Texture: {
+        size: (u32, u32),
+        pixels: Bytes,
+    },
+    Level: {
+        textures: Vec<AssetRef<Texture>>,
+    }
+}
+
+fn compile(path: &std::path::Path) -> Result<(), Error> {
+    let mut compiler = Compiler::new();
+    let mut textures = Vec::new();
+    for i in 0..8 {
+        let name = format!("Texture{}", i);
+        compiler.add_asset_to(
+            "level1",
+            Box::new(Texture {
+                name: name.clone(),
+                size: (128, 128),
+                pixels: Bytes::from(vec![i as u8; 128 * 128 * 4]),
+            }),
+        );
+        textures.push(name.into());
+    }
+    compiler.add_asset_to(
+        "level1",
+        Box::new(Level {
+            name: "Level1".to_string(),
+            textures,
+        }),
+    );
+    compiler.compile_to(File::create(path)?)?;
+    Ok(())
+}
+
+fn main() -> Result<(), Error> {
+    let path = std::env::temp_dir().join("rasset_async_loading.bin");
+    compile(&path)?;
+
+    block_on(async {
+        // Any `futures::io` reader works, such as the files of an async runtime.
+        let file = AllowStdIo::new(File::open(&path)?);
+        let mut loader = Registry::builder()
+            .reg_type::<Texture>()
+            .reg_type::<Level>()
+            .open_async(file)
+            .await?;
+
+        // A single asset can be read without loading its bundle.
+        let texture = loader.load_asset::<Texture>("Texture5").await?.unwrap();
+        println!("Preview of {}: {:?}", texture.name, texture.size);
+
+        // Draw a loading screen while the bundle loads.
+        let progress = loader.progress();
+        let mut shown = 0;
+        let screen = poll_fn(|_| {
+            if progress.entries_done() > shown {
+                shown = progress.entries_done();
+                println!(
+                    "Loading: {}/{} entries, {}/{} bytes ({:.0}%)",
+                    progress.entries_done(),
+                    progress.entries_total(),
+                    progress.bytes_done(),
+                    progress.bytes_total(),
+                    progress.fraction() * 100.0
+                );
+            }
+            if shown > 0 && progress.is_complete() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        });
+        let (loaded, ()) = join(loader.load_bundle("level1"), screen).await;
+        loaded?;
+
+        let registry = loader.into_registry();
+        let level = registry.get_asset::<Level>("Level1").unwrap();
+        println!("{} uses {} textures", level.name, level.textures.len());
+        Ok::<_, Error>(())
+    })?;
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use rasset::prelude::*;
+
+asset_def! {
+    Sprite: {
+        size: (u32, u32),
+        texture: String,
+    }
+}
+
+fn sprite(name: &str, size: (u32, u32), texture: &str) -> Box<Sprite> {
+    Box::new(Sprite {
+        name: name.to_string(),
+        size,
+        texture: texture.to_string(),
+    })
+}
+
+fn main() -> Result<(), Error> {
+    let binary = {
+        let mut compiler = Compiler::new();
+        compiler.add_asset_to("common", sprite("Player", (64, 64), "player.png"));
+        compiler.add_asset_to("level1", sprite("Slime", (32, 32), "slime.png"));
+        compiler.add_asset_to("level2", sprite("Dragon", (256, 256), "dragon.png"));
+        compiler.compile()?
+    };
+
+    let mut registry = Registry::builder().reg_type::<Sprite>().load(&binary)?;
+    println!("Loaded registry with {} assets", registry.amount());
+
+    // Shared assets stay alive after their bundle is unloaded.
+    let slime = registry.get_arc::<Sprite>("Slime").unwrap();
+    let report = registry.unload_bundle("level1")?;
+    println!(
+        "Unloaded {} with {} assets ({} still in use), {} remaining",
+        report.bundle.name,
+        report.assets,
+        report.outstanding,
+        registry.amount()
+    );
+    println!("Slime: {:?}", registry.get_asset::<Sprite>("Slime"));
+    let background = std::thread::spawn(move || slime.texture.clone());
+    println!(
+        "Slime texture in background: {}",
+        background.join().unwrap()
+    );
+
+    registry.load_bundle(&binary, "level1")?;
+    println!("Slime: {:?}", registry.get_asset::<Sprite>("Slime"));
+
+    for bundle in registry.bundles() {
+        println!("Bundle {}: {}", bundle.name, bundle.hash);
+    }
+
+    Ok(())
+}
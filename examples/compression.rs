@@ -0,0 +1,49 @@
+use rasset::prelude::*;
+
+asset_def! {
+    Sprite: {
+        size: (u32, u32),
+        texture: Vec<u8>,
+    },
+    Label: {
+        text: String,
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let mut compiler = Compiler::new()
+        .with_compression(Codec::Zstd)
+        .with_type_compression::<Label>(Codec::Lz4)
+        .with_compression_threshold(256);
+    compiler.add_asset(Box::new(Sprite {
+        name: "License".to_string(),
+        size: (64, 64),
+        texture: include_bytes!("../LICENSE").to_vec(),
+    }));
+    compiler.add_asset(Box::new(Sprite {
+        name: "Readme".to_string(),
+        size: (32, 32),
+        texture: include_bytes!("../README.md").to_vec(),
+    }));
+    compiler.add_asset(Box::new(Label {
+        name: "Title".to_string(),
+        text: "Hello".to_string(),
+    }));
+    let compiled_assets = compiler.compile()?;
+
+    for entry in Container::parse(&compiled_assets)?.entries {
+        println!(
+            "{}: {:?}, {} -> {} bytes",
+            entry.metadata.name, entry.codec, entry.raw_len, entry.len
+        );
+    }
+
+    let registry = Registry::builder()
+        .reg_type::<Sprite>()
+        .reg_type::<Label>()
+        .load(&compiled_assets)?;
+    let readme = registry.get_asset::<Sprite>("Readme").unwrap();
+    println!("Readme texture: {} bytes", readme.texture.len());
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use rasset::prelude::*;
+
+asset_def! {
+    Texture: {
+        path: String,
+    },
+    Sprite: {
+        texture: AssetRef<Texture>,
+    },
+    Animation: {
+        #[reference(Sprite)]
+        frames: Vec<String>,
+    },
+    Material: {
+        #[reference(Material)]
+        fallback: Option<String>,
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let mut compiler = Compiler::new();
+    // Added before its frames, but loaded after them.
+    compiler.add_asset(Box::new(Animation {
+        name: "Walk".to_string(),
+        frames: vec!["Walk1".to_string(), "Walk2".to_string()],
+    }));
+    for frame in ["Walk1", "Walk2"] {
+        compiler.add_asset(Box::new(Sprite {
+            name: frame.to_string(),
+            texture: "Atlas".into(),
+        }));
+    }
+    compiler.add_asset(Box::new(Texture {
+        name: "Atlas".to_string(),
+        path: "atlas.png".to_string(),
+    }));
+    let binary = compiler.compile()?;
+
+    let registry = Registry::builder()
+        .reg_type::<Texture>()
+        .reg_type::<Sprite>()
+        .reg_type::<Animation>()
+        .on_load(|texture: &Texture, _| {
+            println!("Loaded texture {}", texture.name);
+            Ok(())
+        })
+        .on_load(|sprite: &Sprite, registry| {
+            let texture = sprite.texture.resolve(registry).unwrap();
+            println!("Loaded sprite {} using {}", sprite.name, texture.path);
+            Ok(())
+        })
+        .on_load(|animation: &Animation, _| {
+            println!("Loaded animation {}", animation.name);
+            Ok(())
+        })
+        .load(&binary)?;
+
+    let names = |assets: Vec<&Metadata>| -> Vec<String> {
+        assets.into_iter().map(|m| m.name.clone()).collect()
+    };
+    println!("Walk depends on {:?}", names(registry.dependencies("Walk")));
+    println!("Atlas is used by {:?}", names(registry.dependents("Atlas")));
+
+    // Materials fall back to each other, which the compiler rejects.
+    let mut compiler = Compiler::new();
+    for (name, fallback) in [("Metal", "Stone"), ("Stone", "Metal")] {
+        compiler.add_asset(Box::new(Material {
+            name: name.to_string(),
+            fallback: Some(fallback.to_string()),
+        }));
+    }
+    if let Err(err) = compiler.compile() {
+        println!("{}", err);
+    }
+
+    Ok(())
+}
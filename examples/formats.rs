@@ -0,0 +1,55 @@
+use rasset::prelude::*;
+
+asset_def! {
+    Sprite: {
+        size: (u32, u32),
+        texture: String,
+    },
+    Sprites: {
+        sprites: Vec<AssetRef<Sprite>>,
+    }
+}
+
+fn compile(format: Format) -> Result<Vec<u8>, Error> {
+    let mut compiler = Compiler::new().with_format(format);
+    compiler.add_asset(Box::new(Sprite {
+        name: "Player".to_string(),
+        size: (64, 64),
+        texture: "player.png".to_string(),
+    }));
+    compiler.add_asset(Box::new(Sprites {
+        name: "All".to_string(),
+        sprites: vec!["Player".into()],
+    }));
+    compiler.compile()
+}
+
+fn main() -> Result<(), Error> {
+    for format in [
+        Format::Bincode,
+        Format::MessagePack,
+        Format::Cbor,
+        Format::Json,
+    ] {
+        let binary = compile(format)?;
+        let registry = Registry::builder()
+            .reg_type::<Sprite>()
+            .reg_type::<Sprites>()
+            .load(&binary)?;
+        let all = registry.get_asset::<Sprites>("All").unwrap();
+        println!(
+            "{:?}: {} bytes, {:?}",
+            Container::parse(&binary)?.header.format,
+            binary.len(),
+            all.sprites[0].resolve(&registry).map(|s| s.size)
+        );
+    }
+
+    // JSON entries can be read without knowing the Rust types.
+    let binary = compile(Format::Json)?;
+    let container = Container::parse(&binary)?;
+    let json = container.entry_data(&container.entries[0])?;
+    println!("{}", String::from_utf8_lossy(&json));
+
+    Ok(())
+}
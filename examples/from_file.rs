@@ -7,7 +7,7 @@ asset_def! {
         texture: String,
     },
     Sprites: {
-        sprites: Vec<String>,
+        sprites: Vec<AssetRef<Sprite>>,
     }
 }
 
@@ -22,11 +22,11 @@ fn main() -> Result<(), Error> {
         .load(&compiled_assets)?;
 
     let sprites = registry.get_asset::<Sprites>("Sprites").unwrap();
-    for sprite_name in &sprites.sprites {
-        println!(
-            "Sprite: {:#?}",
-            registry.get_asset::<Sprite>(sprite_name).unwrap()
-        );
+    for sprite in &sprites.sprites {
+        println!("Sprite: {:#?}", sprite.resolve(&registry).unwrap());
+    }
+    for bundle in registry.bundles() {
+        println!("Bundle: {}", bundle.name);
     }
 
     Ok(())
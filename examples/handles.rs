@@ -0,0 +1,65 @@
+use rasset::prelude::*;
+
+asset_def! {
+    Sprite: {
+        size: (u32, u32),
+        texture: String,
+    }
+}
+
+fn compile(bundle: &str, sprites: &[(&str, u32)]) -> Result<Vec<u8>, Error> {
+    let mut compiler = Compiler::new();
+    for &(name, size) in sprites {
+        compiler.add_asset_to(
+            bundle,
+            Box::new(Sprite {
+                name: name.to_string(),
+                size: (size, size),
+                texture: format!("{}.png", name.to_lowercase()),
+            }),
+        );
+    }
+    compiler.compile()
+}
+
+/// Component that stores a handle instead of a reference into the registry.
+struct Renderable {
+    sprite: Handle<Sprite>,
+}
+
+fn main() -> Result<(), Error> {
+    let level = compile("level1", &[("Player", 64), ("Slime", 32)])?;
+    let mut registry = Registry::builder().reg_type::<Sprite>().load(&level)?;
+
+    let player = Renderable {
+        sprite: registry.handle::<Sprite>("Player").unwrap(),
+    };
+    let slime = Renderable {
+        sprite: registry.handle::<Sprite>("Slime").unwrap(),
+    };
+    let handles = std::thread::spawn(move || (player, slime)).join().unwrap();
+    println!(
+        "Player: {:?}",
+        registry.get(handles.0.sprite).map(|s| s.size)
+    );
+
+    // Reloading keeps handles of assets that are still present.
+    registry.reload(&compile("level1", &[("Player", 128)])?)?;
+    println!(
+        "Player after reload: {:?}",
+        registry.get(handles.0.sprite).map(|s| s.size)
+    );
+    println!(
+        "Slime after reload: {:?}",
+        registry.get(handles.1.sprite).map(|s| s.size)
+    );
+
+    registry.unload_bundle("level1")?;
+    registry.load_bundle(&level, "level1")?;
+    println!(
+        "Player handle valid after unload: {}",
+        registry.is_valid(handles.0.sprite)
+    );
+
+    Ok(())
+}
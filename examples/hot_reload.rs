@@ -0,0 +1,55 @@
+use rasset::prelude::*;
+use std::time::{Duration, Instant};
+
+asset_def! {
+    Sprite: {
+        size: (u32, u32),
+        texture: String,
+    }
+}
+
+fn compile(sprites: &[(&str, u32)]) -> Result<Vec<u8>, Error> {
+    let mut compiler = Compiler::new();
+    for &(name, size) in sprites {
+        compiler.add_asset(Box::new(Sprite {
+            name: name.to_string(),
+            size: (size, size),
+            texture: format!("{}.png", name.to_lowercase()),
+        }));
+    }
+    compiler.compile()
+}
+
+fn main() -> Result<(), Error> {
+    let path = std::env::temp_dir().join("rasset_hot_reload_example.bin");
+    std::fs::write(&path, compile(&[("Player", 64), ("Enemy", 32)])?)?;
+
+    let mut registry = Registry::builder().reg_type::<Sprite>().build()?;
+    registry.watch(&path)?;
+    let events = registry.subscribe();
+    println!("Watching {} assets", registry.amount());
+
+    // An artist resizes the player, removes the enemy and adds a boss.
+    std::fs::write(&path, compile(&[("Player", 128), ("Boss", 256)])?)?;
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        match registry.poll_changes() {
+            Ok(changes) if !changes.is_empty() => break,
+            Ok(_) => {}
+            Err(error) => println!("Reload failed, keeping previous assets: {}", error),
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    for event in events.try_iter() {
+        println!("{:?} {} ({})", event.kind, event.name, event.type_name);
+    }
+    println!(
+        "Player: {:?}",
+        registry
+            .get_asset::<Sprite>("Player")
+            .map(|player| player.size)
+    );
+
+    Ok(())
+}
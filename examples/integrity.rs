@@ -0,0 +1,47 @@
+use rasset::prelude::*;
+
+asset_def! {
+    Sprite: {
+        size: (u32, u32),
+        texture: String,
+    }
+}
+
+assets!(
+    PlayerSprite: Sprite {
+        size: (64, 64),
+        texture: "path/to/player_texture.png".to_string(),
+    }
+);
+
+fn load(binary: &[u8]) -> Result<Registry, Error> {
+    Registry::builder().reg_type::<Sprite>().load(binary)
+}
+
+fn main() -> Result<(), Error> {
+    let compiled_assets = compile_assets()?;
+    println!("Intact: {} assets", load(&compiled_assets)?.amount());
+
+    let entry = &Container::parse(&compiled_assets)?.entries[0];
+    let mut flipped = compiled_assets.clone();
+    flipped[(entry.offset + entry.len) as usize - 1] ^= 0x01;
+    if let Err(error) = load(&flipped) {
+        println!("Bit flip: {}", error);
+    }
+
+    let unverified = Registry::builder()
+        .reg_type::<Sprite>()
+        .with_verification(false)
+        .load(&flipped)?;
+    println!(
+        "Bit flip without verification: {:?}",
+        unverified.get_asset::<Sprite>("PlayerSprite")
+    );
+
+    let truncated = &compiled_assets[..compiled_assets.len() - 8];
+    if let Err(error) = load(truncated) {
+        println!("Truncated: {}", error);
+    }
+
+    Ok(())
+}
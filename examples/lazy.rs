@@ -0,0 +1,48 @@
+use rasset::prelude::*;
+
+asset_def! {
+    Sprite: {
+        size: (u32, u32),
+        texture: Bytes,
+    }
+}
+
+assets!(
+    PlayerSprite: Sprite {
+        size: (64, 64),
+        texture: include_bytes!("../LICENSE").to_vec().into(),
+    },
+    EnemySprite: Sprite {
+        size: (32, 32),
+        texture: include_bytes!("../README.md").to_vec().into(),
+    }
+);
+
+fn main() -> Result<(), Error> {
+    let compiled_assets = compile_assets()?;
+
+    // Only the entry table is read, the sprites are deserialized when they are requested.
+    let mut registry = Registry::builder()
+        .reg_type::<Sprite>()
+        .with_load_mode(LoadMode::Lazy)
+        .load(&compiled_assets)?;
+    println!("Loaded registry with {} assets", registry.amount());
+
+    let player = registry.try_get_asset::<Sprite>("PlayerSprite")?.unwrap();
+    println!(
+        "Player: {:?}, {} byte texture",
+        player.size,
+        player.texture.len()
+    );
+
+    // Deserialize the sprites that were not requested yet and release their bytes.
+    registry.load_all()?;
+    let enemy = registry.get_asset::<Sprite>("EnemySprite").unwrap();
+    println!(
+        "Enemy: {:?}, {} byte texture",
+        enemy.size,
+        enemy.texture.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use rasset::prelude::*;
+
+asset_def! {
+    Sprite: {
+        size: (u32, u32),
+        texture: String,
+    },
+    Sound: {
+        path: String,
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let mut compiler = Compiler::new();
+    for (name, size) in [("Player", 64), ("Enemy", 32), ("Boss", 128)] {
+        compiler.add_asset(Box::new(Sprite {
+            name: name.to_string(),
+            size: (size, size),
+            texture: format!("{}.png", name.to_lowercase()),
+        }));
+    }
+    compiler.add_asset(Box::new(Sound {
+        name: "Theme".to_string(),
+        path: "theme.ogg".to_string(),
+    }));
+    let mut binary = compiler.compile()?;
+
+    // Damage the entry of the second sprite.
+    let entry = &Container::parse(&binary)?.entries[1];
+    binary[(entry.offset + entry.len) as usize - 1] ^= 0x01;
+
+    // `Sound` is not registered and the damaged sprite fails its checksum.
+    let builder = || Registry::builder().reg_type::<Sprite>();
+    if let Err(error) = builder().load(&binary) {
+        println!("Fail fast: {}", error);
+    }
+
+    let (registry, report) = builder().load_with_report(&binary)?;
+    for failure in &report.failures {
+        println!(
+            "Entry {} ({} '{}'): {}",
+            failure.entry, failure.type_name, failure.name, failure.error
+        );
+    }
+    if !report.is_complete() {
+        println!(
+            "Loaded {} of {} assets",
+            registry.amount(),
+            registry.amount() + report.failures.len()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use rasset::prelude::*;
+
+/// The sprite type as it was when the old pack was compiled.
+mod v1 {
+    use rasset::prelude::*;
+
+    asset_def! {
+        #[asset(id = "Sprite")]
+        Sprite: {
+            size: (u32, u32),
+            texture: String,
+        }
+    }
+}
+
+asset_def! {
+    #[asset(id = "Sprite", version = 2)]
+    Sprite: {
+        size: (u32, u32),
+        texture: String,
+        opacity: f32,
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let mut compiler = Compiler::new();
+    compiler.add_asset(Box::new(v1::Sprite {
+        name: "Player".to_string(),
+        size: (64, 64),
+        texture: "player.png".to_string(),
+    }));
+    let old_pack = compiler.compile()?;
+
+    if let Err(error) = Registry::builder().reg_type::<Sprite>().load(&old_pack) {
+        println!("Without migration: {}", error);
+    }
+
+    let registry = Registry::builder()
+        .reg_type_with_migrations::<Sprite>()
+        .migrate(1, |old: v1::Sprite| Sprite {
+            name: old.name,
+            size: old.size,
+            texture: old.texture,
+            opacity: 1.0,
+        })
+        .done()
+        .load(&old_pack)?;
+    println!("Migrated: {:?}", registry.get_asset::<Sprite>("Player"));
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use rasset::prelude::*;
+
+asset_def! {
+    Sprite: {
+        size: (u32, u32),
+        texture: Bytes,
+    }
+}
+
+assets!(
+    PlayerSprite: Sprite {
+        size: (64, 64),
+        texture: include_bytes!("../LICENSE").to_vec().into(),
+    },
+    EnemySprite: Sprite {
+        size: (32, 32),
+        texture: include_bytes!("../README.md").to_vec().into(),
+    }
+);
+
+fn main() -> Result<(), Error> {
+    let path = std::env::temp_dir().join("rasset_mmap_example.bin");
+    std::fs::write(&path, compile_assets()?)?;
+
+    let registry = Registry::builder().reg_type::<Sprite>().open_mmap(&path)?;
+
+    for name in ["PlayerSprite", "EnemySprite"] {
+        let sprite = registry.get_asset::<Sprite>(name).unwrap();
+        println!(
+            "{}: {:?}, {} byte texture, borrowed from the file: {}",
+            name,
+            sprite.size,
+            sprite.texture.len(),
+            sprite.texture.is_shared()
+        );
+    }
+
+    Ok(())
+}
@@ -16,6 +16,14 @@ impl Asset for SpriteAsset {
         std::any::type_name::<Self>()
     }
 
+    fn type_tag(&self) -> &'static str {
+        "sprite"
+    }
+
+    fn static_type_tag() -> &'static str {
+        "sprite"
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -0,0 +1,42 @@
+use rasset::prelude::*;
+use std::time::Instant;
+
+asset_def! {
+    Mesh: {
+        vertices: Vec<(f32, f32, f32)>,
+        indices: Vec<u32>,
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let mut compiler = Compiler::new();
+    for i in 0..256 {
+        compiler.add_asset(Box::new(Mesh {
+            name: format!("Mesh{}", i),
+            vertices: (0..4096).map(|v| (v as f32, i as f32, 0.0)).collect(),
+            indices: (0..4096).collect(),
+        }));
+    }
+
+    // Assets are serialized on all threads, but written in the order they were added.
+    let start = Instant::now();
+    let binary = compiler.compile()?;
+    println!(
+        "Compiled {} bytes in {:?} on {} threads",
+        binary.len(),
+        start.elapsed(),
+        rayon::current_num_threads()
+    );
+
+    let start = Instant::now();
+    let registry = Registry::builder().reg_type::<Mesh>().load(&binary)?;
+    println!(
+        "Loaded {} meshes in {:?}",
+        registry.amount(),
+        start.elapsed()
+    );
+
+    let mesh = registry.get_asset::<Mesh>("Mesh100").unwrap();
+    println!("{} has {} vertices", mesh.name, mesh.vertices.len());
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use rasset::prelude::*;
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom, Write},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+asset_def! {
+    Texture: {
+        size: (u32, u32),
+        pixels: Bytes,
+    }
+}
+
+/// Reader counting the bytes read through it.
+struct Counting<R> {
+    inner: R,
+    read: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for Counting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.read.fetch_add(read as u64, Ordering::Relaxed);
+        Ok(read)
+    }
+}
+
+impl<R: Seek> Seek for Counting<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let mut compiler = Compiler::new();
+    for i in 0..64 {
+        compiler.add_asset(Box::new(Texture {
+            name: format!("Texture{}", i),
+            size: (256, 256),
+            pixels: Bytes::from(vec![i as u8; 256 * 256 * 4]),
+        }));
+    }
+
+    // Store the pack after a header of an outer archive.
+    let path = std::env::temp_dir().join("rasset_partial_reads.bin");
+    let mut file = File::create(&path)?;
+    file.write_all(b"OUTER ARCHIVE HEADER")?;
+    let file = compiler.compile_to(file)?;
+    println!("Pack file is {} bytes", file.metadata()?.len());
+
+    let mut file = File::open(&path)?;
+    file.seek(SeekFrom::Start(20))?;
+    let read = Arc::new(AtomicU64::new(0));
+    let reader = Counting {
+        inner: BufReader::new(file),
+        read: read.clone(),
+    };
+    let registry = Registry::builder()
+        .reg_type::<Texture>()
+        .with_load_mode(LoadMode::Lazy)
+        .load_from(reader)?;
+    println!(
+        "Loaded {} assets, read {} bytes",
+        registry.amount(),
+        read.load(Ordering::Relaxed)
+    );
+
+    let texture = registry.get_asset::<Texture>("Texture42").unwrap();
+    println!(
+        "Texture42 is {:?} filled with {}, read {} bytes",
+        texture.size,
+        texture.pixels[0],
+        read.load(Ordering::Relaxed)
+    );
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use rasset::prelude::*;
+
+asset_def! {
+    Sprite: {
+        width: i64,
+        height: i64,
+        texture: String,
+    },
+    Sprites: {
+        sprites: Vec<AssetRef<Sprite>>,
+    }
+}
+
+fn main() -> Result<(), Error> {
+    // Same file as the `from_file` example, read at runtime instead of by `asset_file!`.
+    let registry = Registry::builder()
+        .reg_type::<Sprite>()
+        .reg_type::<Sprites>()
+        .load_ron_file("examples/assets.ron")?;
+
+    let sprites = registry.get_asset::<Sprites>("Sprites").unwrap();
+    for sprite in &sprites.sprites {
+        println!("Sprite: {:?}", sprite.resolve(&registry).unwrap());
+    }
+    println!(
+        "Bundles: {:?}",
+        registry.bundles().map(|b| &b.name).collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
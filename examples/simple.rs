@@ -1,6 +1,7 @@
 use rasset::prelude::*;
 
 asset_def! {
+    #[asset(id = "sprite")]
     Sprite: {
         size: (u32, u32),
         texture: String,
@@ -28,6 +29,12 @@ fn main() -> Result<(), Error> {
     println!("Loaded registry with {} assets", registry.amount());
     println!("Player: {:?}", registry.get_asset::<Sprite>("PlayerSprite"));
     println!("Enemy: {:?}", registry.get_asset::<Sprite>("EnemySprite"));
+    for metadata in registry.iter_metadata() {
+        println!(
+            "{} ({}): {}",
+            metadata.name, metadata.type_name, metadata.hash
+        );
+    }
 
     Ok(())
 }
@@ -0,0 +1,63 @@
+use rasset::prelude::*;
+use std::{fs::File, io::BufWriter};
+
+asset_def! {
+    Texture: {
+        size: (u32, u32),
+        pixels: Bytes,
+    },
+    Sprite: {
+        #[reference(Texture)]
+        texture: String,
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let path = std::env::temp_dir().join("rasset_streaming.bin");
+    let compiler = Compiler::new();
+
+    // Every texture is serialized and written as soon as it is added, so only one is kept
+    // in memory at a time.
+    let mut writer = compiler.writer(BufWriter::new(File::create(&path)?))?;
+    for i in 0..16 {
+        let texture = Texture {
+            name: format!("Texture{}", i),
+            size: (256, 256),
+            pixels: Bytes::from(vec![i as u8; 256 * 256 * 4]),
+        };
+        writer.add_asset_to("textures", &texture)?;
+    }
+    writer.add_asset(&Sprite {
+        name: "Player".to_string(),
+        texture: "Texture3".to_string(),
+    })?;
+    writer.finish()?;
+
+    let binary = std::fs::read(&path)?;
+    println!("Wrote {} bytes to {}", binary.len(), path.display());
+    let registry = Registry::builder()
+        .reg_type::<Texture>()
+        .reg_type::<Sprite>()
+        .load(&binary)?;
+    let player = registry.get_asset::<Sprite>("Player").unwrap();
+    let texture = registry.get_asset::<Texture>(&player.texture).unwrap();
+    println!(
+        "Player uses {} ({:?}, {} bytes)",
+        texture.name,
+        texture.size,
+        texture.pixels.len()
+    );
+
+    // Assets added to a compiler can be written to a file the same way.
+    let mut compiler = Compiler::new();
+    compiler.add_asset(Box::new(Sprite {
+        name: "Enemy".to_string(),
+        texture: "Enemy".to_string(),
+    }));
+    if let Err(error) = compiler.compile_to(BufWriter::new(File::create(&path)?)) {
+        println!("Failed: {}", error);
+    }
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
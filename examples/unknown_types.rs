@@ -0,0 +1,80 @@
+use rasset::prelude::*;
+
+asset_def! {
+    Sprite: {
+        size: (u32, u32),
+        texture: String,
+    },
+    Sound: {
+        data: Bytes,
+        #[reference(Sprite)]
+        icon: String,
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let mut compiler = Compiler::new();
+    compiler.add_asset(Box::new(Sprite {
+        name: "Player".to_string(),
+        size: (64, 64),
+        texture: "player.png".to_string(),
+    }));
+    compiler.add_asset_to(
+        "audio",
+        Box::new(Sound {
+            name: "Jump".to_string(),
+            data: Bytes::from(vec![1, 2, 3, 4]),
+            icon: "Player".to_string(),
+        }),
+    );
+    let binary = compiler.compile()?;
+
+    // A tool that only knows about sprites.
+    let tool = || Registry::builder().reg_type::<Sprite>();
+    if let Err(error) = tool().load(&binary) {
+        println!("Error policy: {}", error);
+    }
+
+    let registry = tool()
+        .with_unknown_type_policy(UnknownTypePolicy::Skip)
+        .load(&binary)?;
+    for skipped in registry.skipped() {
+        println!("Skipped {} '{}'", skipped.type_name, skipped.name);
+    }
+
+    // Resize the sprite and write the sounds back unchanged.
+    let registry = tool()
+        .with_unknown_type_policy(UnknownTypePolicy::Raw)
+        .load(&binary)?;
+    let mut compiler = Compiler::new();
+    for sprite in registry.get_assets::<Sprite>() {
+        compiler.add_asset(Box::new(Sprite {
+            size: (128, 128),
+            ..sprite.clone()
+        }));
+    }
+    for (bundle, raw) in registry.raw_assets() {
+        println!("Keeping {} '{}' from {}", raw.type_tag, raw.name, bundle);
+        compiler.add_raw_to(bundle, raw.clone());
+    }
+    let binary = compiler.compile()?;
+
+    let registry = Registry::builder()
+        .reg_type::<Sprite>()
+        .reg_type::<Sound>()
+        .load(&binary)?;
+    let sound = registry.get_asset::<Sound>("Jump").unwrap();
+    println!("Jump: {:?}, icon {}", &sound.data[..], sound.icon);
+    println!(
+        "Player: {:?}",
+        registry.get_asset::<Sprite>("Player").map(|s| s.size)
+    );
+    let dependencies: Vec<&str> = registry
+        .dependencies("Jump")
+        .into_iter()
+        .map(|metadata| metadata.name.as_str())
+        .collect();
+    println!("Jump depends on {:?}", dependencies);
+
+    Ok(())
+}
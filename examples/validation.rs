@@ -0,0 +1,71 @@
+use rasset::prelude::*;
+
+asset_def! {
+    Sprite: {
+        #[range(1..=4096)]
+        size: (u32, u32),
+        #[range(0.0..=1.0)]
+        opacity: f32,
+        texture: String,
+    }
+}
+
+/// The sprite type of an older build, before sizes were checked.
+mod old {
+    use rasset::prelude::*;
+
+    asset_def! {
+        #[asset(id = "Sprite")]
+        Sprite: {
+            size: (u32, u32),
+            opacity: f32,
+            texture: String,
+        }
+    }
+}
+
+fn sprite(name: &str, size: (u32, u32), opacity: f32) -> Box<Sprite> {
+    Box::new(Sprite {
+        name: name.to_string(),
+        size,
+        opacity,
+        texture: format!("{}.png", name.to_lowercase()),
+    })
+}
+
+fn main() -> Result<(), Error> {
+    let mut compiler = Compiler::new();
+    compiler.add_asset(sprite("Player", (64, 64), 1.0));
+    compiler.add_asset(sprite("Ghost", (64, 64), 1.5));
+    compiler.add_asset(sprite("Empty", (0, 0), 0.0));
+    if let Err(Error::Validation(failures)) = compiler.compile() {
+        for failure in failures {
+            println!("Compile: {}", failure);
+        }
+    }
+
+    // A container compiled before the check was added is validated when it is loaded.
+    let mut compiler = Compiler::new();
+    compiler.add_asset(Box::new(old::Sprite {
+        name: "Empty".to_string(),
+        size: (0, 0),
+        opacity: 1.0,
+        texture: "empty.png".to_string(),
+    }));
+    let binary = compiler.compile()?;
+    match Registry::builder().reg_type::<Sprite>().load(&binary) {
+        Ok(_) => println!("Loaded"),
+        Err(err) => println!("Load: {}", err),
+    }
+
+    let registry = Registry::builder()
+        .reg_type::<Sprite>()
+        .with_validation(false)
+        .load(&binary)?;
+    println!(
+        "Without validation: {:?}",
+        registry.get_asset::<Sprite>("Empty").map(|s| s.size)
+    );
+
+    Ok(())
+}
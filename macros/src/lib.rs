@@ -1,21 +1,26 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{ToTokens, quote};
 use syn::{
-    braced,
+    FieldValue, Ident, Member, Token, Type, braced,
     parse::{Parse, ParseStream, Result},
     punctuated::Punctuated,
     token::Comma,
-    FieldValue, Ident, Member, Token, Type,
 };
 
 struct AssetDefInput {
     struct_name: Ident,
+    type_tag: syn::LitStr,
+    schema_version: syn::LitInt,
     fields: Punctuated<FieldDef, Comma>,
 }
 
 struct FieldDef {
     name: Ident,
     ty: Type,
+    /// Asset type the names in the field refer to, set with `#[reference(T)]`.
+    reference: Option<Type>,
+    /// Range the values of the field must be in, set with `#[range(..)]`.
+    range: Option<syn::Expr>,
 }
 
 struct AssetDefsInput {
@@ -31,18 +36,59 @@ impl Parse for AssetDefsInput {
 
 impl Parse for FieldDef {
     fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let mut reference = None;
+        let mut range = None;
+        for attr in &attrs {
+            if attr.path().is_ident("reference") {
+                reference = Some(attr.parse_args::<Type>()?);
+            } else if attr.path().is_ident("range") {
+                range = Some(attr.parse_args::<syn::Expr>()?);
+            } else {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "Expected #[reference(AssetType)] or #[range(..)]",
+                ));
+            }
+        }
+
         let name: Ident = input.parse()?;
         input.parse::<Token![:]>()?;
         let ty: Type = input.parse()?;
-        Ok(FieldDef { name, ty })
+        Ok(FieldDef {
+            name,
+            ty,
+            reference,
+            range,
+        })
     }
 }
 
 impl Parse for AssetDefInput {
     fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
         let struct_name: Ident = input.parse()?;
         let _colon: Token![:] = input.parse()?;
 
+        let mut type_tag = syn::LitStr::new(&struct_name.to_string(), struct_name.span());
+        let mut schema_version = syn::LitInt::new("1", struct_name.span());
+        for attr in &attrs {
+            if !attr.path().is_ident("asset") {
+                return Err(syn::Error::new_spanned(attr, "Expected #[asset(...)]"));
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("id") {
+                    type_tag = meta.value()?.parse()?;
+                    Ok(())
+                } else if meta.path.is_ident("version") {
+                    schema_version = meta.value()?.parse()?;
+                    Ok(())
+                } else {
+                    Err(meta.error("Unsupported asset option"))
+                }
+            })?;
+        }
+
         let content;
         braced!(content in input);
 
@@ -50,6 +96,8 @@ impl Parse for AssetDefInput {
 
         Ok(AssetDefInput {
             struct_name,
+            type_tag,
+            schema_version,
             fields,
         })
     }
@@ -60,11 +108,24 @@ pub fn asset_def(input: TokenStream) -> TokenStream {
     let AssetDefsInput { defs } = syn::parse_macro_input!(input as AssetDefsInput);
 
     let mut expanded_tokens = proc_macro2::TokenStream::new();
+    let mut type_tags = std::collections::HashMap::new();
 
     for def in defs {
         let struct_name = &def.struct_name;
+        let type_tag = &def.type_tag;
+        let schema_version = &def.schema_version;
         let fields = &def.fields;
 
+        if let Some(other) = type_tags.insert(type_tag.value(), struct_name.clone()) {
+            let message = format!(
+                "Asset type id \"{}\" is already used by {}",
+                type_tag.value(),
+                other
+            );
+            expanded_tokens.extend(syn::Error::new(type_tag.span(), message).to_compile_error());
+            continue;
+        }
+
         let name_ident = syn::Ident::new("name", proc_macro2::Span::call_site());
         let string_type: syn::Type = syn::parse_quote!(String);
 
@@ -76,8 +137,65 @@ pub fn asset_def(input: TokenStream) -> TokenStream {
             .chain(fields.iter().map(|f| f.ty.clone()))
             .collect();
 
+        let reference_fields: Vec<&Ident> = fields
+            .iter()
+            .filter(|field| mentions_ident(field.ty.to_token_stream(), "AssetRef"))
+            .map(|field| &field.name)
+            .collect();
+        let (named_fields, named_targets): (Vec<&Ident>, Vec<&Type>) = fields
+            .iter()
+            .filter_map(|field| Some((&field.name, field.reference.as_ref()?)))
+            .unzip();
+        let references = if reference_fields.is_empty() && named_fields.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                fn references(&self) -> Vec<rasset::prelude::Reference> {
+                    let mut refs = Vec::new();
+                    #(rasset::prelude::CollectRefs::collect_refs(&self.#reference_fields, &mut refs);)*
+                    #(rasset::prelude::ReferenceNames::reference_names(&self.#named_fields, &mut |name| {
+                        refs.push(rasset::prelude::Reference::to::<#named_targets>(name))
+                    });)*
+                    refs
+                }
+            }
+        };
+
+        let (range_fields, ranges): (Vec<&Ident>, Vec<&syn::Expr>) = fields
+            .iter()
+            .filter_map(|field| Some((&field.name, field.range.as_ref()?)))
+            .unzip();
+        let validate = if range_fields.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                fn validate(&self, ctx: &rasset::prelude::ValidationContext) -> Result<(), rasset::prelude::Error> {
+                    let mut failures: Vec<String> = Vec::new();
+                    #({
+                        let range = #ranges;
+                        if !rasset::prelude::InRange::in_range(&self.#range_fields, &range) {
+                            failures.push(format!("{} {:?} is outside {:?}", stringify!(#range_fields), self.#range_fields, range));
+                        }
+                    })*
+                    if failures.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(ctx.error(failures.join(", ")))
+                    }
+                }
+            }
+        };
+
         let expanded = quote! {
-            #[derive(Debug, Clone, bincode::Encode, bincode::Decode)]
+            #[derive(
+                Debug,
+                Clone,
+                bincode::Encode,
+                bincode::Decode,
+                rasset::prelude::serde::Serialize,
+                rasset::prelude::serde::Deserialize,
+            )]
+            #[serde(crate = "rasset::prelude::serde")]
             pub struct #struct_name {
                 #(pub #field_names: #field_types),*
             }
@@ -91,6 +209,22 @@ pub fn asset_def(input: TokenStream) -> TokenStream {
                     std::any::type_name::<Self>()
                 }
 
+                fn type_tag(&self) -> &'static str {
+                    #type_tag
+                }
+
+                fn static_type_tag() -> &'static str {
+                    #type_tag
+                }
+
+                fn schema_version(&self) -> u32 {
+                    #schema_version
+                }
+
+                fn static_schema_version() -> u32 {
+                    #schema_version
+                }
+
                 fn as_any(&self) -> &dyn std::any::Any {
                     self
                 }
@@ -99,6 +233,10 @@ pub fn asset_def(input: TokenStream) -> TokenStream {
                     self.name.clone()
                 }
 
+                #references
+
+                #validate
+
                 fn to_bytes(&self) -> Result<Vec<u8>, rasset::prelude::Error> {
                     bincode::encode_to_vec(self, bincode::config::standard())
                         .map_err(|e| rasset::prelude::Error::Serialization(format!("Failed to serialize {}: {}", stringify!(#struct_name), e)))
@@ -109,6 +247,28 @@ pub fn asset_def(input: TokenStream) -> TokenStream {
                         .map_err(|e| rasset::prelude::Error::Deserialization(format!("Failed to deserialize {}: {}", stringify!(#struct_name), e)))
                         .map(|(asset, _)| asset)
                 }
+
+                fn to_bytes_as(&self, format: rasset::prelude::Format) -> Result<Vec<u8>, rasset::prelude::Error> {
+                    match format {
+                        rasset::prelude::Format::Bincode => self.to_bytes(),
+                        format => format.serialize(self),
+                    }
+                }
+
+                fn from_bytes_as(bytes: &[u8], format: rasset::prelude::Format) -> Result<Self, rasset::prelude::Error> {
+                    match format {
+                        rasset::prelude::Format::Bincode => Self::from_bytes(bytes),
+                        format => format.deserialize(bytes),
+                    }
+                }
+
+                fn from_deserializer<'de, D>(deserializer: D) -> Result<Self, rasset::prelude::Error>
+                where
+                    D: rasset::prelude::serde::Deserializer<'de>,
+                {
+                    <Self as rasset::prelude::serde::Deserialize>::deserialize(deserializer)
+                        .map_err(|e| rasset::prelude::Error::Deserialization(format!("Failed to deserialize {}: {}", stringify!(#struct_name), e)))
+                }
             }
         };
 
@@ -155,34 +315,37 @@ impl Parse for AssetsInput {
 pub fn assets(input: TokenStream) -> TokenStream {
     let AssetsInput { assets } = syn::parse_macro_input!(input as AssetsInput);
 
-    let asset_inits = assets.iter().map(|asset| {
-        let AssetInstance { name, ty, fields } = asset;
-        let name_str = name.to_string();
+    let asset_inits = assets
+        .iter()
+        .map(|asset| {
+            let AssetInstance { name, ty, fields } = asset;
+            let name_str = name.to_string();
 
-        let mut field_inits = fields
-            .iter()
-            .map(|field| {
-                let field_name = match &field.member {
-                    Member::Named(ident) => ident,
-                    _ => panic!("Expected named field"),
-                };
-                let expr = &field.expr;
-                quote! { #field_name: #expr }
-            })
-            .collect::<Vec<_>>();
-
-        field_inits.insert(0, quote! { name: #name_str.to_string() });
-
-        quote! {
-            {
-                let mut asset = #ty {
-                    #(#field_inits),*
-                };
-                asset.name = #name_str.to_string();
-                asset
+            let mut field_inits = fields
+                .iter()
+                .map(|field| {
+                    let field_name = match &field.member {
+                        Member::Named(ident) => ident,
+                        _ => panic!("Expected named field"),
+                    };
+                    let expr = &field.expr;
+                    quote! { #field_name: #expr }
+                })
+                .collect::<Vec<_>>();
+
+            field_inits.insert(0, quote! { name: #name_str.to_string() });
+
+            quote! {
+                {
+                    let mut asset = #ty {
+                        #(#field_inits),*
+                    };
+                    asset.name = #name_str.to_string();
+                    asset
+                }
             }
-        }
-    });
+        })
+        .collect::<Vec<_>>();
 
     let expanded = quote! {
         pub fn compile_assets() -> Result<Vec<u8>, Error> {
@@ -190,6 +353,12 @@ pub fn assets(input: TokenStream) -> TokenStream {
             #(compiler.add_asset(Box::new(#asset_inits));)*
             Ok(compiler.compile()?.to_vec())
         }
+
+        pub fn compile_assets_to<W: std::io::Write + std::io::Seek>(writer: W) -> Result<W, Error> {
+            let mut compiler = rasset::prelude::Compiler::new();
+            #(compiler.add_asset(Box::new(#asset_inits));)*
+            compiler.compile_to(writer)
+        }
     };
 
     TokenStream::from(expanded)
@@ -211,42 +380,59 @@ pub fn asset_file(input: TokenStream) -> TokenStream {
         name: String,
         #[serde(rename = "type")]
         ty: String,
+        #[serde(default)]
+        bundle: Option<String>,
         metadata: std::collections::BTreeMap<ron::Value, ron::Value>,
     }
 
-    let parsed: Vec<Asset> = ron::from_str(&contents)
+    let parsed: Vec<Asset> = ron::Options::default()
+        .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+        .from_str(&contents)
         .unwrap_or_else(|e| panic!("Failed parsing RON from {}: {}", file_path, e));
 
-    let asset_tokens = parsed.into_iter().map(|asset| {
-        let _name_ident = syn::Ident::new(&asset.name, proc_macro2::Span::call_site());
-        let ty_ident = syn::Ident::new(&asset.ty, proc_macro2::Span::call_site());
-
-        let field_inits = asset.metadata.iter().map(|(k, v)| {
-            let key: String = k.clone().into_rust().expect("Key must be a string");
-            let ident = syn::Ident::new(&key, proc_macro2::Span::call_site());
-
-            let expr = value_to_expr(v);
-            quote! { #ident: #expr }
-        });
-
-        let name_string = &asset.name;
-        quote! {
-            {
-                let mut asset = #ty_ident {
-                    name: #name_string.to_string(),
-                    #(#field_inits),*
-                };
-                asset
+    let asset_tokens = parsed
+        .into_iter()
+        .map(|asset| {
+            let _name_ident = syn::Ident::new(&asset.name, proc_macro2::Span::call_site());
+            let ty_ident = syn::Ident::new(&asset.ty, proc_macro2::Span::call_site());
+
+            let field_inits = asset.metadata.iter().map(|(k, v)| {
+                let key: String = k.clone().into_rust().expect("Key must be a string");
+                let ident = syn::Ident::new(&key, proc_macro2::Span::call_site());
+
+                let expr = value_to_expr(v);
+                quote! { #ident: #expr }
+            });
+
+            let name_string = &asset.name;
+            let bundle = match &asset.bundle {
+                Some(bundle) => quote! { #bundle },
+                None => quote! { rasset::prelude::Bundle::DEFAULT },
+            };
+            quote! {
+                compiler.add_asset_to(#bundle, Box::new({
+                    let mut asset = #ty_ident {
+                        name: #name_string.to_string(),
+                        #(#field_inits),*
+                    };
+                    asset
+                }));
             }
-        }
-    });
+        })
+        .collect::<Vec<_>>();
 
     let expanded = quote! {
         pub fn compile_assets() -> Result<Vec<u8>, Error> {
             let mut compiler = rasset::prelude::Compiler::new();
-            #(compiler.add_asset(Box::new(#asset_tokens));)*
+            #(#asset_tokens)*
             Ok(compiler.compile()?.to_vec())
         }
+
+        pub fn compile_assets_to<W: std::io::Write + std::io::Seek>(writer: W) -> Result<W, Error> {
+            let mut compiler = rasset::prelude::Compiler::new();
+            #(#asset_tokens)*
+            compiler.compile_to(writer)
+        }
     };
 
     TokenStream::from(expanded)
@@ -327,7 +513,7 @@ fn value_to_expr(value: &ron::Value) -> proc_macro2::TokenStream {
                 let path = s.trim_start_matches("!IncludeVec ");
                 return quote! { include_bytes!(#path).to_vec() };
             }
-            quote! { #s.to_string() }
+            quote! { #s.into() }
         }
         ron::Value::Seq(seq) => {
             let elements: Vec<_> = seq.iter().map(value_to_expr).collect();
@@ -339,3 +525,12 @@ fn value_to_expr(value: &ron::Value) -> proc_macro2::TokenStream {
         }
     }
 }
+
+/// Returns whether a token stream contains the given identifier, including inside groups.
+fn mentions_ident(tokens: proc_macro2::TokenStream, ident: &str) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(found) => found == ident,
+        proc_macro2::TokenTree::Group(group) => mentions_ident(group.stream(), ident),
+        _ => false,
+    })
+}
@@ -1,4 +1,6 @@
-use crate::{error::Error, r#type::Type};
+use crate::{
+    error::Error, format::Format, reference::Reference, r#type::Type, validation::ValidationContext,
+};
 use std::any::Any;
 
 /// Trait representing a generic asset in the system.
@@ -9,9 +11,60 @@ pub trait Asset: Send + Sync + Any + 'static {
     /// Returns the type name of the asset as a string.
     fn type_name(&self) -> &'static str;
 
+    /// Returns the stable identifier of the asset type, written into compiled containers.
+    ///
+    /// Unlike [`Asset::type_name`] it does not change when the type is moved between modules
+    /// or crates. Must return the same value as [`Asset::static_type_tag`], and like it
+    /// defaults to the full path of the Rust type.
+    fn type_tag(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Returns the stable identifier of the asset type without an instance.
+    fn static_type_tag() -> &'static str
+    where
+        Self: Sized,
+    {
+        std::any::type_name::<Self>()
+    }
+
+    /// Returns the version of the serialized layout of the asset type, written into compiled
+    /// containers.
+    ///
+    /// Entries with an older version are converted by the migrations registered with
+    /// `RegistryBuilder::reg_type_with_migrations`. Must return the same value as
+    /// [`Asset::static_schema_version`].
+    fn schema_version(&self) -> u32 {
+        1
+    }
+
+    /// Returns the version of the serialized layout of the asset type without an instance.
+    fn static_schema_version() -> u32
+    where
+        Self: Sized,
+    {
+        1
+    }
+
     /// Returns the name of the asset.
     fn name(&self) -> String;
 
+    /// Returns the assets this asset refers to with [`AssetRef`](crate::prelude::AssetRef)
+    /// fields, which must be compiled together with it.
+    fn references(&self) -> Vec<Reference> {
+        Vec::new()
+    }
+
+    /// Checks the invariants of the asset, run by
+    /// [`Compiler::compile`](crate::prelude::Compiler::compile) and after the asset is loaded.
+    ///
+    /// Failures should be created with [`ValidationContext::error`]. `asset_def!` implements
+    /// this for fields marked with `#[range(..)]`.
+    fn validate(&self, ctx: &ValidationContext) -> Result<(), Error> {
+        let _ = ctx;
+        Ok(())
+    }
+
     /// Returns a reference to the asset as a trait object for dynamic type checking.
     fn as_any(&self) -> &dyn Any;
 
@@ -22,4 +75,50 @@ pub trait Asset: Send + Sync + Any + 'static {
     fn from_bytes(bytes: &[u8]) -> Result<Self, Error>
     where
         Self: Sized;
+
+    /// Serializes the asset in the given format.
+    ///
+    /// [`Format::Bincode`] uses [`Asset::to_bytes`]. Assets defined with `asset_def!` use
+    /// serde for the other formats, other assets fail unless they override this.
+    fn to_bytes_as(&self, format: Format) -> Result<Vec<u8>, Error> {
+        match format {
+            Format::Bincode => self.to_bytes(),
+            format => Err(Error::Serialization(format!(
+                "Asset type {} does not support the {:?} format",
+                self.type_name(),
+                format
+            ))),
+        }
+    }
+
+    /// Deserializes the asset from bytes in the given format, see [`Asset::to_bytes_as`].
+    fn from_bytes_as(bytes: &[u8], format: Format) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        match format {
+            Format::Bincode => Self::from_bytes(bytes),
+            format => Err(Error::Deserialization(format!(
+                "Asset type {} does not support the {:?} format",
+                std::any::type_name::<Self>(),
+                format
+            ))),
+        }
+    }
+
+    /// Deserializes the asset from a serde deserializer, used by the runtime RON loader.
+    ///
+    /// Assets defined with `asset_def!` derive serde and implement this. Other assets fail
+    /// with [`Error::Deserialization`] unless they override it.
+    fn from_deserializer<'de, D>(deserializer: D) -> Result<Self, Error>
+    where
+        Self: Sized,
+        D: serde::Deserializer<'de>,
+    {
+        let _ = deserializer;
+        Err(Error::Deserialization(format!(
+            "Asset type {} does not support serde deserialization",
+            std::any::type_name::<Self>()
+        )))
+    }
 }
@@ -0,0 +1,78 @@
+//! Side channel for [`Bytes`] payloads that are stored outside of the serialized asset.
+//!
+//! While an asset is serialized by the [`Compiler`](crate::prelude::Compiler), its `Bytes`
+//! fields are appended to a thread-local buffer and only their index is written into the
+//! asset. The buffer is stored next to the entry at an aligned offset, so the payloads can be
+//! borrowed directly from a memory-mapped container. While an asset is deserialized, the
+//! payloads of its entry are provided through the same mechanism.
+
+use crate::bytes::Bytes;
+use std::{cell::RefCell, ops::Range};
+
+/// Alignment of blob payloads inside a container.
+pub const BLOB_ALIGN: usize = 64;
+
+/// Payloads collected while serializing a single asset.
+#[derive(Default)]
+pub(crate) struct Blobs {
+    /// Concatenated payloads, each starting at a multiple of [`BLOB_ALIGN`].
+    pub(crate) data: Vec<u8>,
+    /// Range of each payload in `data`.
+    pub(crate) ranges: Vec<Range<usize>>,
+}
+
+impl Blobs {
+    /// Appends a payload at the next aligned offset, returning its index.
+    pub(crate) fn append(&mut self, bytes: &[u8]) -> u32 {
+        let start = self.data.len().next_multiple_of(BLOB_ALIGN);
+        self.data.resize(start, 0);
+        self.data.extend_from_slice(bytes);
+        self.ranges.push(start..self.data.len());
+        (self.ranges.len() - 1) as u32
+    }
+}
+
+thread_local! {
+    static SINK: RefCell<Option<Blobs>> = const { RefCell::new(None) };
+    static SOURCE: RefCell<Option<Vec<Bytes>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` while collecting the payloads of [`Bytes`] values it serializes.
+pub(crate) fn collect<R>(f: impl FnOnce() -> R) -> (R, Blobs) {
+    let previous = SINK.with(|sink| sink.replace(Some(Blobs::default())));
+    let result = f();
+    let blobs = SINK.with(|sink| sink.replace(previous)).unwrap_or_default();
+    (result, blobs)
+}
+
+/// Appends a payload to the active sink, returning its index, or `None` if no sink is active.
+///
+/// A payload equal to one already collected reuses its index. Encoders that run a sizing pass
+/// first, such as `bincode::encode_to_vec`, push every payload twice.
+pub(crate) fn push(bytes: &[u8]) -> Option<u32> {
+    SINK.with(|sink| {
+        let mut sink = sink.borrow_mut();
+        let blobs = sink.as_mut()?;
+        if let Some(index) = blobs
+            .ranges
+            .iter()
+            .position(|range| &blobs.data[range.clone()] == bytes)
+        {
+            return Some(index as u32);
+        }
+        Some(blobs.append(bytes))
+    })
+}
+
+/// Runs `f` while providing the payloads of the entry it deserializes.
+pub(crate) fn provide<R>(blobs: Vec<Bytes>, f: impl FnOnce() -> R) -> R {
+    let previous = SOURCE.with(|source| source.replace(Some(blobs)));
+    let result = f();
+    SOURCE.with(|source| source.replace(previous));
+    result
+}
+
+/// Returns the payload with the given index from the active source.
+pub(crate) fn get(index: u32) -> Option<Bytes> {
+    SOURCE.with(|source| source.borrow().as_ref()?.get(index as usize).cloned())
+}
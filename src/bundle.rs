@@ -1,7 +1,45 @@
-use crate::{asset::Asset, metadata::Metadata};
+use crate::{asset::Asset, metadata::Metadata, raw::RawAsset};
 
 /// Represents a collection of assets along with its metadata.
+///
+/// Bundles are compiled into the same container and can be loaded and unloaded from a
+/// [`Registry`](crate::prelude::Registry) independently.
 pub struct Bundle {
     pub metadata: Metadata,
     pub assets: Vec<Box<dyn Asset>>,
+    /// Assets of unregistered types, written after `assets`.
+    pub raw: Vec<RawAsset>,
+}
+
+impl Bundle {
+    /// Name of the bundle that assets added with `Compiler::add_asset` are compiled into.
+    pub const DEFAULT: &'static str = "default";
+
+    /// Creates an empty bundle with the given name.
+    pub fn new(name: impl Into<String>) -> Self {
+        Bundle {
+            metadata: Metadata {
+                name: name.into(),
+                type_name: std::any::type_name::<Bundle>().to_string(),
+                hash: String::new(),
+            },
+            assets: Vec::new(),
+            raw: Vec::new(),
+        }
+    }
+
+    /// Returns the name of the bundle.
+    pub fn name(&self) -> &str {
+        &self.metadata.name
+    }
+
+    /// Adds an asset to the bundle.
+    pub fn add_asset(&mut self, asset: Box<dyn Asset>) {
+        self.assets.push(asset);
+    }
+
+    /// Adds an asset of an unregistered type to the bundle.
+    pub fn add_raw(&mut self, asset: RawAsset) {
+        self.raw.push(asset);
+    }
 }
@@ -0,0 +1,180 @@
+use crate::{blob, prelude::bincode};
+use std::{fmt, ops::Deref, ops::Range, sync::Arc};
+
+/// Shared, immutable memory that [`Bytes`] can borrow from, such as a memory-mapped file.
+pub type SharedSource = Arc<dyn AsRef<[u8]> + Send + Sync>;
+
+/// Byte buffer for large asset fields such as textures or audio.
+///
+/// Compiled containers store `Bytes` payloads at aligned offsets outside of the serialized
+/// asset. When a container is loaded from shared memory (see `RegistryBuilder::open_mmap`),
+/// the payloads are borrowed from it instead of being copied into the heap.
+#[derive(Clone)]
+pub struct Bytes(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    Owned(Arc<[u8]>),
+    Shared {
+        source: SharedSource,
+        range: Range<usize>,
+    },
+}
+
+impl Bytes {
+    /// Creates a buffer borrowing `range` of a shared source.
+    ///
+    /// Panics if the range is out of bounds of the source.
+    pub fn from_shared(source: SharedSource, range: Range<usize>) -> Self {
+        assert!(
+            range.start <= range.end && range.end <= (*source).as_ref().len(),
+            "Range {:?} is out of bounds of the shared source",
+            range
+        );
+        Bytes(Repr::Shared { source, range })
+    }
+
+    /// Returns whether the contents are borrowed from a shared source.
+    pub fn is_shared(&self) -> bool {
+        matches!(self.0, Repr::Shared { .. })
+    }
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match &self.0 {
+            Repr::Owned(bytes) => bytes,
+            Repr::Shared { source, range } => &(**source).as_ref()[range.clone()],
+        }
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Default for Bytes {
+    fn default() -> Self {
+        Bytes::from(Vec::new())
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Bytes(Repr::Owned(bytes.into()))
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(bytes: &[u8]) -> Self {
+        Bytes(Repr::Owned(bytes.into()))
+    }
+}
+
+impl fmt::Debug for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for Bytes {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for Bytes {}
+
+/// Payload stored inline in the serialized asset.
+const INLINE: u8 = 0;
+/// Payload stored next to the entry, referenced by index.
+const BLOB: u8 = 1;
+
+impl bincode::Encode for Bytes {
+    fn encode<E: bincode::enc::Encoder>(
+        &self,
+        encoder: &mut E,
+    ) -> Result<(), bincode::error::EncodeError> {
+        match blob::push(self) {
+            Some(index) => {
+                BLOB.encode(encoder)?;
+                index.encode(encoder)
+            }
+            None => {
+                INLINE.encode(encoder)?;
+                (**self).encode(encoder)
+            }
+        }
+    }
+}
+
+impl<Context> bincode::Decode<Context> for Bytes {
+    fn decode<D: bincode::de::Decoder<Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        match u8::decode(decoder)? {
+            INLINE => Vec::<u8>::decode(decoder).map(Bytes::from),
+            BLOB => {
+                let index = u32::decode(decoder)?;
+                blob::get(index).ok_or_else(|| {
+                    bincode::error::DecodeError::OtherString(format!(
+                        "Blob {} is not available",
+                        index
+                    ))
+                })
+            }
+            found => Err(bincode::error::DecodeError::UnexpectedVariant {
+                type_name: "Bytes",
+                allowed: &bincode::error::AllowedEnumVariants::Range { min: 0, max: 1 },
+                found: found as u32,
+            }),
+        }
+    }
+}
+
+bincode::impl_borrow_decode!(Bytes);
+
+impl serde::Serialize for Bytes {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Bytes {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Bytes;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a byte array")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, bytes: &[u8]) -> Result<Bytes, E> {
+                Ok(Bytes::from(bytes))
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, bytes: Vec<u8>) -> Result<Bytes, E> {
+                Ok(Bytes::from(bytes))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Bytes, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(Bytes::from(bytes))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(Visitor)
+    }
+}
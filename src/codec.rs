@@ -0,0 +1,77 @@
+use crate::{error::Error, prelude::bincode};
+
+/// Compression codec of an entry in a compiled container.
+///
+/// Every codec is part of the container format, but compressing or decompressing with one
+/// requires its cargo feature (`zstd`, `lz4` or `deflate`). Otherwise
+/// [`Error::CodecUnavailable`] is returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, bincode::Encode, bincode::Decode)]
+pub enum Codec {
+    /// The entry is stored uncompressed.
+    #[default]
+    None,
+    Zstd,
+    Lz4,
+    Deflate,
+}
+
+impl Codec {
+    /// Compresses `bytes` with this codec.
+    pub fn compress(self, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            Codec::None => Ok(bytes.to_vec()),
+            #[cfg(feature = "zstd")]
+            Codec::Zstd => zstd::bulk::compress(bytes, 0)
+                .map_err(|e| Error::Compilation(format!("Failed to compress with zstd: {}", e))),
+            #[cfg(feature = "lz4")]
+            Codec::Lz4 => Ok(lz4_flex::compress(bytes)),
+            #[cfg(feature = "deflate")]
+            Codec::Deflate => {
+                use std::io::Write;
+                let mut encoder =
+                    flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                Ok(encoder.finish()?)
+            }
+            #[allow(unreachable_patterns)]
+            codec => Err(Error::CodecUnavailable(codec)),
+        }
+    }
+
+    /// Decompresses `bytes` that were compressed with this codec from `len` bytes.
+    pub fn decompress(self, bytes: &[u8], len: usize) -> Result<Vec<u8>, Error> {
+        let decompressed = match self {
+            Codec::None => bytes.to_vec(),
+            #[cfg(feature = "zstd")]
+            Codec::Zstd => zstd::bulk::decompress(bytes, len).map_err(|e| {
+                Error::Deserialization(format!("Failed to decompress with zstd: {}", e))
+            })?,
+            #[cfg(feature = "lz4")]
+            Codec::Lz4 => lz4_flex::decompress(bytes, len).map_err(|e| {
+                Error::Deserialization(format!("Failed to decompress with lz4: {}", e))
+            })?,
+            #[cfg(feature = "deflate")]
+            Codec::Deflate => {
+                use std::io::Read;
+                let mut decompressed = Vec::with_capacity(len);
+                flate2::read::DeflateDecoder::new(bytes)
+                    .read_to_end(&mut decompressed)
+                    .map_err(|e| {
+                        Error::Deserialization(format!("Failed to decompress with deflate: {}", e))
+                    })?;
+                decompressed
+            }
+            #[allow(unreachable_patterns)]
+            codec => return Err(Error::CodecUnavailable(codec)),
+        };
+
+        if decompressed.len() != len {
+            return Err(Error::Deserialization(format!(
+                "Decompressed {} bytes, expected {}",
+                decompressed.len(),
+                len
+            )));
+        }
+        Ok(decompressed)
+    }
+}
@@ -1,8 +1,39 @@
-use crate::{asset::Asset, error::Error, prelude::bincode};
+use crate::{
+    asset::Asset,
+    blob,
+    bundle::Bundle,
+    codec::Codec,
+    container::{ContainerWriter, Payload},
+    error::Error,
+    format::Format,
+    metadata::Metadata,
+    policy::DuplicatePolicy,
+    raw::RawAsset,
+    reference::Reference,
+    r#type::Type,
+    validation::{self, ValidationStage},
+};
+use std::{
+    any::TypeId,
+    collections::{HashMap, hash_map},
+    io::{Cursor, Seek, Write},
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 /// Compiler is responsible for compiling a collection of assets into a binary format.
 pub struct Compiler {
+    /// Assets of the [`Bundle::DEFAULT`] bundle.
     pub assets: Vec<Box<dyn Asset>>,
+    /// Assets of unregistered types of the [`Bundle::DEFAULT`] bundle.
+    pub raw: Vec<RawAsset>,
+    pub bundles: Vec<Bundle>,
+    duplicates: DuplicatePolicy,
+    compression: Codec,
+    type_compression: HashMap<Type, Codec>,
+    compression_threshold: usize,
+    format: Format,
 }
 
 impl Default for Compiler {
@@ -14,24 +45,657 @@ impl Default for Compiler {
 impl Compiler {
     /// Creates a new instance of the Compiler.
     pub fn new() -> Self {
-        Compiler { assets: Vec::new() }
+        Compiler {
+            assets: Vec::new(),
+            raw: Vec::new(),
+            bundles: Vec::new(),
+            duplicates: DuplicatePolicy::default(),
+            compression: Codec::None,
+            type_compression: HashMap::new(),
+            compression_threshold: 0,
+            format: Format::default(),
+        }
+    }
+
+    /// Sets how assets with the same type and name are handled by [`Compiler::compile`].
+    pub fn with_duplicate_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicates = policy;
+        self
     }
 
-    /// Adds an asset to the compiler's collection.
+    /// Sets the codec entries are compressed with, unless overridden for their type.
+    pub fn with_compression(mut self, codec: Codec) -> Self {
+        self.compression = codec;
+        self
+    }
+
+    /// Sets the codec entries of the asset type `T` are compressed with.
+    pub fn with_type_compression<T: Asset>(mut self, codec: Codec) -> Self {
+        self.type_compression.insert(Type(TypeId::of::<T>()), codec);
+        self
+    }
+
+    /// Sets the serialized size in bytes below which entries are stored uncompressed.
+    pub fn with_compression_threshold(mut self, bytes: usize) -> Self {
+        self.compression_threshold = bytes;
+        self
+    }
+
+    /// Sets the format entries are serialized in, recorded in the container header.
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Adds an asset to the compiler's default bundle.
     pub fn add_asset(&mut self, asset: Box<dyn Asset>) {
         self.assets.push(asset);
     }
 
-    /// Compiles all added assets into a binary format.
+    /// Adds an asset to the bundle with the given name, creating the bundle if needed.
+    pub fn add_asset_to(&mut self, bundle: &str, asset: Box<dyn Asset>) {
+        if bundle == Bundle::DEFAULT {
+            self.assets.push(asset);
+        } else if let Some(existing) = self.bundles.iter_mut().find(|b| b.name() == bundle) {
+            existing.add_asset(asset);
+        } else {
+            let mut new = Bundle::new(bundle);
+            new.add_asset(asset);
+            self.bundles.push(new);
+        }
+    }
+
+    /// Adds an asset of an unregistered type to the compiler's default bundle.
+    ///
+    /// The asset is written with its original type tag, bytes and dependencies, without
+    /// being deserialized or validated. It is checked for duplicates like other assets.
+    pub fn add_raw(&mut self, asset: RawAsset) {
+        self.raw.push(asset);
+    }
+
+    /// Adds an asset of an unregistered type to the bundle with the given name, creating the
+    /// bundle if needed.
+    pub fn add_raw_to(&mut self, bundle: &str, asset: RawAsset) {
+        if bundle == Bundle::DEFAULT {
+            self.raw.push(asset);
+        } else if let Some(existing) = self.bundles.iter_mut().find(|b| b.name() == bundle) {
+            existing.add_raw(asset);
+        } else {
+            let mut new = Bundle::new(bundle);
+            new.add_raw(asset);
+            self.bundles.push(new);
+        }
+    }
+
+    /// Adds a bundle, merging its assets into an existing bundle with the same name.
+    pub fn add_bundle(&mut self, bundle: Bundle) {
+        let name = bundle.metadata.name;
+        for asset in bundle.assets {
+            self.add_asset_to(&name, asset);
+        }
+        for asset in bundle.raw {
+            self.add_raw_to(&name, asset);
+        }
+    }
+
+    /// Compiles all added bundles into a versioned container.
+    ///
+    /// Assets are serialized in the configured [`Format`]. The entry table stores the
+    /// [`Metadata`] of every asset, including a hash of its serialized bytes. The default
+    /// bundle is only written if it contains assets. Entries are compressed with the
+    /// configured [`Codec`] when they are not smaller than the compression threshold.
+    ///
+    /// The references of every asset form a dependency graph, stored in the entry table with
+    /// an order in which every asset comes after its dependencies. Fails with
+    /// [`Error::DanglingReference`] if an asset refers to an asset that is not compiled and
+    /// with [`Error::DependencyCycle`] if assets depend on each other in a cycle.
+    ///
+    /// Every asset is checked with [`Asset::validate`], failing with [`Error::Validation`]
+    /// listing all invalid assets.
+    ///
+    /// Raw assets are written after all other assets, so when a raw asset has the same type
+    /// tag and name as another asset, the [`DuplicatePolicy`] treats it as added last.
     pub fn compile(&self) -> Result<Vec<u8>, Error> {
-        let mut assets: Vec<(String, Vec<u8>)> = Vec::new();
-        for asset in &self.assets {
-            let type_name = asset.type_name().to_string();
-            let bytes = asset.to_bytes()?;
-            assets.push((type_name, bytes));
+        self.compile_to(Cursor::new(Vec::new()))
+            .map(Cursor::into_inner)
+    }
+
+    /// Compiles all added bundles like [`Compiler::compile`], writing the container to
+    /// `writer` at its current position instead of building it in memory.
+    ///
+    /// Returns the writer positioned after the container. If compiling fails, the bytes
+    /// written so far do not form a valid container.
+    pub fn compile_to<W: Write + Seek>(&self, writer: W) -> Result<W, Error> {
+        let assets = self.unique_assets()?;
+        let names: Vec<&str> = self.bundle_names().collect();
+        let mut writer = self.writer(writer)?;
+        for name in &names {
+            writer.bundle(name);
+        }
+        #[cfg(not(feature = "parallel"))]
+        for (bundle, asset) in assets {
+            writer.add_asset_to(names[bundle], asset)?;
+        }
+        // Assets are prepared in parallel a chunk at a time, so that only one serialized
+        // asset per thread is held in memory, and written in their original order.
+        #[cfg(feature = "parallel")]
+        for chunk in assets.chunks(rayon::current_num_threads()) {
+            let prepared: Vec<_> = chunk
+                .par_iter()
+                .map(|&(_, asset)| self.prepare(asset))
+                .collect();
+            for (&(bundle, asset), prepared) in chunk.iter().zip(prepared) {
+                writer.add_prepared(names[bundle], asset, prepared)?;
+            }
+        }
+        for (bundle, asset) in self.bundle_raw() {
+            writer.add_raw_to(names[bundle], asset)?;
+        }
+        writer.finish()
+    }
+
+    /// Starts a container at the current position of `writer` that assets are written to as
+    /// they are added, with the settings of this compiler.
+    ///
+    /// Assets added to the compiler itself are not written. Only the entry table is kept in
+    /// memory, so packs larger than the available memory can be compiled.
+    pub fn writer<W: Write + Seek>(&self, writer: W) -> Result<CompilerWriter<'_, W>, Error> {
+        Ok(CompilerWriter {
+            compiler: self,
+            writer: ContainerWriter::new(writer, self.format)?,
+            bundles: HashMap::new(),
+            nodes: Vec::new(),
+            assets: HashMap::new(),
+            tags: HashMap::new(),
+            type_tags: HashMap::new(),
+            raw_tags: HashMap::new(),
+            invalid: Vec::new(),
+        })
+    }
+
+    /// Validates, serializes and compresses an asset, independently of the other assets.
+    fn prepare(&self, asset: &dyn Asset) -> Result<Prepared, Error> {
+        let invalid = validation::validate(asset, ValidationStage::Compile).err();
+        let (bytes, blobs) = blob::collect(|| asset.to_bytes_as(self.format));
+        let bytes = bytes?;
+        let metadata = Metadata::from_parts(
+            asset.name(),
+            asset.type_name().to_string(),
+            &[&bytes, &blobs.data],
+        );
+        let codec = if bytes.len() < self.compression_threshold {
+            Codec::None
+        } else {
+            self.codec(asset)
+        };
+        Ok(Prepared {
+            metadata,
+            payload: Payload::new(bytes, codec, blobs)?,
+            invalid,
+        })
+    }
+
+    /// Returns the codec configured for the type of an asset.
+    fn codec(&self, asset: &dyn Asset) -> Codec {
+        self.type_compression
+            .get(&asset.get_type())
+            .copied()
+            .unwrap_or(self.compression)
+    }
+
+    /// Returns the names of the bundles to compile, in container order.
+    fn bundle_names(&self) -> impl Iterator<Item = &str> {
+        let default = self.has_default_bundle().then_some(Bundle::DEFAULT);
+        default
+            .into_iter()
+            .chain(self.bundles.iter().map(Bundle::name))
+    }
+
+    /// Returns the assets of all bundles with the index of their bundle in
+    /// [`Compiler::bundle_names`].
+    fn bundle_assets(&self) -> impl Iterator<Item = (usize, &dyn Asset)> {
+        let default = self.assets.iter().map(|asset| (0, asset.as_ref()));
+        let offset = usize::from(self.has_default_bundle());
+        let bundles = self
+            .bundles
+            .iter()
+            .enumerate()
+            .flat_map(move |(index, bundle)| {
+                bundle
+                    .assets
+                    .iter()
+                    .map(move |asset| (index + offset, asset.as_ref()))
+            });
+        default.chain(bundles)
+    }
+
+    /// Returns the raw assets of all bundles with the index of their bundle in
+    /// [`Compiler::bundle_names`].
+    fn bundle_raw(&self) -> impl Iterator<Item = (usize, &RawAsset)> {
+        let default = self.raw.iter().map(|asset| (0, asset));
+        let offset = usize::from(self.has_default_bundle());
+        let bundles = self
+            .bundles
+            .iter()
+            .enumerate()
+            .flat_map(move |(index, bundle)| {
+                bundle.raw.iter().map(move |asset| (index + offset, asset))
+            });
+        default.chain(bundles)
+    }
+
+    /// Returns whether the default bundle has any assets and is written.
+    fn has_default_bundle(&self) -> bool {
+        !self.assets.is_empty() || !self.raw.is_empty()
+    }
+
+    /// Resolves assets sharing a type and a name according to the duplicate policy.
+    ///
+    /// Duplicates are detected across all bundles. The remaining asset keeps the position of
+    /// the first asset with its type and name, but moves to the bundle of the asset it came from.
+    /// Raw assets are left out, the writer checks them when they are written.
+    fn unique_assets(&self) -> Result<Vec<(usize, &dyn Asset)>, Error> {
+        let mut unique: Vec<(usize, &dyn Asset)> = Vec::new();
+        let mut seen: HashMap<(Type, String), usize> = HashMap::new();
+        for (bundle, asset) in self.bundle_assets() {
+            match seen.entry((asset.get_type(), asset.name())) {
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(unique.len());
+                    unique.push((bundle, asset));
+                }
+                hash_map::Entry::Occupied(entry) => match self.duplicates {
+                    DuplicatePolicy::Error => {
+                        return Err(Error::DuplicateAsset {
+                            type_name: asset.type_name().to_string(),
+                            name: entry.key().1.clone(),
+                        });
+                    }
+                    DuplicatePolicy::FirstWins => {}
+                    DuplicatePolicy::LastWins => unique[*entry.get()] = (bundle, asset),
+                },
+            }
+        }
+
+        Ok(unique)
+    }
+}
+
+/// Asset validated, serialized and compressed by [`Compiler::prepare`].
+struct Prepared {
+    metadata: Metadata,
+    payload: Payload,
+    /// Validation failure of the asset, reported by [`CompilerWriter::finish`].
+    invalid: Option<Error>,
+}
+
+/// Whether an asset added to a [`CompilerWriter`] is written.
+enum Admission {
+    /// The asset is a duplicate skipped under [`DuplicatePolicy::FirstWins`].
+    Skip,
+    /// The asset is written, replacing the entry at `replaces` under
+    /// [`DuplicatePolicy::LastWins`].
+    Write { replaces: Option<u32> },
+}
+
+/// Asset added to a [`CompilerWriter`], kept to build the dependency graph.
+struct Node {
+    name: String,
+    references: Vec<Target>,
+}
+
+/// Asset referred to by a [`Node`].
+enum Target {
+    /// Reference returned by [`Asset::references`].
+    Asset(Reference),
+    /// Dependency of a [`RawAsset`] by type tag and name.
+    Raw(String, String),
+}
+
+/// Container being written by [`Compiler::writer`], serializing every asset as it is added.
+///
+/// Duplicates are handled by the duplicate policy of the compiler. Under
+/// [`DuplicatePolicy::LastWins`] the replaced asset is still written, but no longer listed in
+/// the entry table.
+pub struct CompilerWriter<'a, W> {
+    compiler: &'a Compiler,
+    writer: ContainerWriter<W>,
+    /// Index of every started bundle by name.
+    bundles: HashMap<String, u32>,
+    /// Every written entry, by entry index.
+    nodes: Vec<Node>,
+    /// Entry index of every asset by type and name.
+    assets: HashMap<(Type, String), u32>,
+    /// Entry index of every asset by type tag and name.
+    tags: HashMap<(String, String), u32>,
+    type_tags: HashMap<&'static str, (Type, &'static str)>,
+    /// Type name of every type tag that is only used by raw assets.
+    raw_tags: HashMap<String, String>,
+    /// Validation failures reported by [`CompilerWriter::finish`].
+    invalid: Vec<Error>,
+}
+
+impl<W: Write + Seek> CompilerWriter<'_, W> {
+    /// Returns the index of the bundle with the given name, starting it if needed.
+    fn bundle(&mut self, name: &str) -> u32 {
+        if let Some(&index) = self.bundles.get(name) {
+            return index;
+        }
+        let index = self.writer.push_bundle(name.to_string());
+        self.bundles.insert(name.to_string(), index);
+        index
+    }
+
+    /// Serializes an asset into the default bundle.
+    pub fn add_asset(&mut self, asset: &dyn Asset) -> Result<(), Error> {
+        self.add_asset_to(Bundle::DEFAULT, asset)
+    }
+
+    /// Serializes an asset into the bundle with the given name.
+    ///
+    /// The asset is validated, but failures are only reported by [`CompilerWriter::finish`].
+    pub fn add_asset_to(&mut self, bundle: &str, asset: &dyn Asset) -> Result<(), Error> {
+        match self.admit(asset)? {
+            Admission::Skip => Ok(()),
+            Admission::Write { replaces } => {
+                let prepared = self.compiler.prepare(asset)?;
+                self.write_asset(bundle, asset, prepared, replaces)
+            }
+        }
+    }
+
+    /// Writes an asset that was already prepared, applying the same checks as
+    /// [`CompilerWriter::add_asset_to`].
+    #[cfg(feature = "parallel")]
+    fn add_prepared(
+        &mut self,
+        bundle: &str,
+        asset: &dyn Asset,
+        prepared: Result<Prepared, Error>,
+    ) -> Result<(), Error> {
+        match self.admit(asset)? {
+            Admission::Skip => Ok(()),
+            Admission::Write { replaces } => self.write_asset(bundle, asset, prepared?, replaces),
+        }
+    }
+
+    /// Checks the type tag of an asset and applies the duplicate policy to it.
+    fn admit(&mut self, asset: &dyn Asset) -> Result<Admission, Error> {
+        let type_tag = asset.type_tag();
+        let (r#type, type_name) = self
+            .type_tags
+            .entry(type_tag)
+            .or_insert_with(|| (asset.get_type(), asset.type_name()));
+        if *r#type != asset.get_type() {
+            return Err(Error::TypeTagCollision {
+                type_tag: type_tag.to_string(),
+                first: type_name.to_string(),
+                second: asset.type_name().to_string(),
+            });
+        }
+        if let Some(first) = self.raw_tags.get(type_tag)
+            && first != asset.type_name()
+        {
+            return Err(Error::TypeTagCollision {
+                type_tag: type_tag.to_string(),
+                first: first.clone(),
+                second: asset.type_name().to_string(),
+            });
+        }
+
+        self.duplicate(type_tag, asset.type_name(), &asset.name())
+    }
+
+    /// Checks the type tag of a raw asset against the written assets and applies the
+    /// duplicate policy to it.
+    fn admit_raw(&mut self, asset: &RawAsset) -> Result<Admission, Error> {
+        let first = match self.type_tags.get(asset.type_tag.as_str()) {
+            Some((_, type_name)) => type_name.to_string(),
+            None => self
+                .raw_tags
+                .entry(asset.type_tag.clone())
+                .or_insert_with(|| asset.type_name.clone())
+                .clone(),
+        };
+        if first != asset.type_name {
+            return Err(Error::TypeTagCollision {
+                type_tag: asset.type_tag.clone(),
+                first,
+                second: asset.type_name.clone(),
+            });
         }
 
-        bincode::encode_to_vec(&assets, bincode::config::standard())
-            .map_err(|e| Error::Serialization(format!("Failed to serialize assets: {}", e)))
+        self.duplicate(&asset.type_tag, &asset.type_name, &asset.name)
+    }
+
+    /// Applies the duplicate policy to an asset with the given type tag and name.
+    fn duplicate(&self, type_tag: &str, type_name: &str, name: &str) -> Result<Admission, Error> {
+        let replaces = self
+            .tags
+            .get(&(type_tag.to_string(), name.to_string()))
+            .copied();
+        if replaces.is_some() {
+            match self.compiler.duplicates {
+                DuplicatePolicy::Error => {
+                    return Err(Error::DuplicateAsset {
+                        type_name: type_name.to_string(),
+                        name: name.to_string(),
+                    });
+                }
+                DuplicatePolicy::FirstWins => return Ok(Admission::Skip),
+                DuplicatePolicy::LastWins => {}
+            }
+        }
+        Ok(Admission::Write { replaces })
+    }
+
+    /// Writes a prepared asset, replacing the entry at `replaces` if given.
+    fn write_asset(
+        &mut self,
+        bundle: &str,
+        asset: &dyn Asset,
+        prepared: Prepared,
+        replaces: Option<u32>,
+    ) -> Result<(), Error> {
+        self.invalid.extend(prepared.invalid);
+        let bundle = self.bundle(bundle);
+        let type_tag = asset.type_tag();
+        let index = self.writer.push(
+            type_tag.to_string(),
+            asset.schema_version(),
+            bundle,
+            prepared.metadata,
+            prepared.payload,
+        )?;
+        let name = asset.name();
+        let node = Node {
+            name: name.clone(),
+            references: asset.references().into_iter().map(Target::Asset).collect(),
+        };
+        let index = self.insert(node, index, replaces);
+        self.assets.insert((asset.get_type(), name.clone()), index);
+        self.tags.insert((type_tag.to_string(), name), index);
+        Ok(())
+    }
+
+    /// Writes an asset of an unregistered type unchanged into the default bundle.
+    pub fn add_raw(&mut self, asset: &RawAsset) -> Result<(), Error> {
+        self.add_raw_to(Bundle::DEFAULT, asset)
+    }
+
+    /// Writes an asset of an unregistered type unchanged into the bundle with the given name.
+    ///
+    /// Fails if the asset is serialized in a different format than the container. Raw assets
+    /// are subject to the same duplicate policy and type tag checks as other assets, keyed by
+    /// their type tag and name.
+    pub fn add_raw_to(&mut self, bundle: &str, asset: &RawAsset) -> Result<(), Error> {
+        let format = self.compiler.format;
+        if asset.format != format {
+            return Err(Error::Compilation(format!(
+                "Raw asset '{}' is serialized as {:?}, but the container uses {:?}",
+                asset.name, asset.format, format
+            )));
+        }
+        let replaces = match self.admit_raw(asset)? {
+            Admission::Skip => return Ok(()),
+            Admission::Write { replaces } => replaces,
+        };
+        let blobs = asset.collect_blobs();
+        let metadata = Metadata::from_parts(
+            asset.name.clone(),
+            asset.type_name.clone(),
+            &[&asset.bytes, &blobs.data],
+        );
+        let codec = if asset.bytes.len() < self.compiler.compression_threshold {
+            Codec::None
+        } else {
+            self.compiler.compression
+        };
+        let payload = Payload::new(asset.bytes.clone(), codec, blobs)?;
+        let bundle = self.bundle(bundle);
+        let index = self.writer.push(
+            asset.type_tag.clone(),
+            asset.schema_version,
+            bundle,
+            metadata,
+            payload,
+        )?;
+        let node = Node {
+            name: asset.name.clone(),
+            references: asset
+                .dependencies
+                .iter()
+                .map(|(type_tag, name)| Target::Raw(type_tag.clone(), name.clone()))
+                .collect(),
+        };
+        let index = self.insert(node, index, replaces);
+        self.tags
+            .insert((asset.type_tag.clone(), asset.name.clone()), index);
+        Ok(())
+    }
+
+    /// Records the node of the entry pushed last, moving it to the entry at `replaced`.
+    ///
+    /// Returns the index the entry ends up at.
+    fn insert(&mut self, node: Node, index: u32, replaced: Option<u32>) -> u32 {
+        match replaced {
+            Some(replaced) => {
+                self.writer.supersede(replaced);
+                self.nodes[replaced as usize] = node;
+                replaced
+            }
+            None => {
+                debug_assert_eq!(index as usize, self.nodes.len());
+                self.nodes.push(node);
+                index
+            }
+        }
+    }
+
+    /// Resolves the references of every entry to entry indices.
+    ///
+    /// Fails with [`Error::DanglingReference`] if a referenced asset is not written.
+    fn dependencies(&self) -> Result<Vec<Vec<u32>>, Error> {
+        self.nodes
+            .iter()
+            .map(|node| {
+                let mut dependencies = Vec::new();
+                for target in &node.references {
+                    let (dependency, type_name, name) = match target {
+                        Target::Asset(reference) => (
+                            self.assets
+                                .get(&(reference.r#type.clone(), reference.name.clone())),
+                            reference.type_name,
+                            &reference.name,
+                        ),
+                        Target::Raw(type_tag, name) => (
+                            self.tags.get(&(type_tag.clone(), name.clone())),
+                            type_tag.as_str(),
+                            name,
+                        ),
+                    };
+                    let dependency = dependency.ok_or_else(|| Error::DanglingReference {
+                        asset: node.name.clone(),
+                        type_name: type_name.to_string(),
+                        name: name.clone(),
+                    })?;
+                    if !dependencies.contains(dependency) {
+                        dependencies.push(*dependency);
+                    }
+                }
+                Ok(dependencies)
+            })
+            .collect()
+    }
+
+    /// Writes the entry table and header, returning the writer positioned after the
+    /// container.
+    ///
+    /// Fails with [`Error::Validation`] if any added asset is invalid, and like
+    /// [`Compiler::compile`] on dangling references and dependency cycles.
+    pub fn finish(mut self) -> Result<W, Error> {
+        validation::report(std::mem::take(&mut self.invalid))?;
+        let dependencies = self.dependencies()?;
+        let names: Vec<String> = self.nodes.iter().map(|node| node.name.clone()).collect();
+        let order = load_order(&names, &dependencies)?;
+        self.writer.finish(dependencies, order)
+    }
+}
+
+/// State of an asset while ordering the dependency graph.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    New,
+    InProgress,
+    Done,
+}
+
+/// Orders the assets so that every asset comes after its dependencies.
+///
+/// Fails with [`Error::DependencyCycle`] if the assets depend on each other in a cycle.
+fn load_order(names: &[String], dependencies: &[Vec<u32>]) -> Result<Vec<u32>, Error> {
+    let mut state = vec![Visit::New; names.len()];
+    let mut order = Vec::with_capacity(names.len());
+    // Assets being visited, with the position of the next dependency to visit. The stack
+    // is explicit so that long chains of references cannot overflow the thread's stack.
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    for root in 0..names.len() {
+        if state[root] != Visit::New {
+            continue;
+        }
+        state[root] = Visit::InProgress;
+        stack.push((root, 0));
+        while let Some((index, next)) = stack.last_mut() {
+            let index = *index;
+            let Some(&dependency) = dependencies[index].get(*next) else {
+                stack.pop();
+                state[index] = Visit::Done;
+                order.push(index as u32);
+                continue;
+            };
+            *next += 1;
+
+            let dependency = dependency as usize;
+            match state[dependency] {
+                Visit::Done => {}
+                Visit::InProgress => {
+                    let start = stack
+                        .iter()
+                        .position(|&(i, _)| i == dependency)
+                        .unwrap_or(0);
+                    let cycle = stack[start..]
+                        .iter()
+                        .map(|&(i, _)| i)
+                        .chain(std::iter::once(dependency))
+                        .map(|i| names[i].clone())
+                        .collect();
+                    return Err(Error::DependencyCycle(cycle));
+                }
+                Visit::New => {
+                    state[dependency] = Visit::InProgress;
+                    stack.push((dependency, 0));
+                }
+            }
+        }
     }
+    Ok(order)
 }
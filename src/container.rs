@@ -0,0 +1,689 @@
+use crate::{
+    blob::{BLOB_ALIGN, Blobs},
+    bundle::Bundle,
+    bytes::{Bytes, SharedSource},
+    codec::Codec,
+    error::Error,
+    format::Format,
+    metadata::Metadata,
+    prelude::bincode,
+};
+use std::{
+    borrow::Cow,
+    io::{Read, Seek, SeekFrom, Write},
+    ops::Range,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// Magic bytes every compiled asset container starts with.
+pub const MAGIC: [u8; 4] = *b"RAST";
+
+/// Container format version written by this version of the library.
+pub const VERSION: u16 = 1;
+
+/// Size in bytes of the fixed header at the start of a container.
+pub const HEADER_LEN: usize = 38;
+
+/// Fixed-size header at the start of a compiled asset container.
+///
+/// Layout (little-endian): magic, version, flags, entry count, table offset, table length,
+/// table checksum and format id. The entry table is stored after the entry payloads so that it can be
+/// written last. It holds a checksum of every entry, and [`Header::table_checksum`] covers the
+/// entry table and every other header field, so together they cover the whole container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub version: u16,
+    pub flags: u16,
+    pub entry_count: u32,
+    pub table_offset: u64,
+    pub table_len: u64,
+    /// xxh3 checksum of the other header fields followed by the entry table.
+    pub table_checksum: u64,
+    /// Format the entries are serialized in. The header and entry table always use bincode.
+    pub format: Format,
+}
+
+impl Header {
+    /// Encodes the header into its on-disk representation.
+    pub fn to_bytes(&self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[0..4].copy_from_slice(&MAGIC);
+        bytes[4..6].copy_from_slice(&self.version.to_le_bytes());
+        bytes[6..8].copy_from_slice(&self.flags.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.entry_count.to_le_bytes());
+        bytes[12..20].copy_from_slice(&self.table_offset.to_le_bytes());
+        bytes[20..28].copy_from_slice(&self.table_len.to_le_bytes());
+        bytes[28..36].copy_from_slice(&self.table_checksum.to_le_bytes());
+        bytes[36..38].copy_from_slice(&self.format.id().to_le_bytes());
+        bytes
+    }
+
+    /// Decodes and checks a header from the start of a container.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (table_offset, table_len) = Header::table_range(bytes)?;
+        let format = u16::from_le_bytes([bytes[36], bytes[37]]);
+        let format = Format::from_id(format).ok_or_else(|| {
+            Error::Deserialization(format!("Unknown entry format {} in header", format))
+        })?;
+
+        Ok(Header {
+            version: VERSION,
+            flags: u16::from_le_bytes([bytes[6], bytes[7]]),
+            entry_count: u32::from_le_bytes(bytes[8..12].try_into().unwrap()),
+            table_offset,
+            table_len,
+            table_checksum: u64::from_le_bytes(bytes[28..36].try_into().unwrap()),
+            format,
+        })
+    }
+
+    /// Checks the magic bytes and version of a header and returns the offset and length of
+    /// the entry table, so that the table can be read before the rest of the header is
+    /// trusted.
+    pub(crate) fn table_range(bytes: &[u8]) -> Result<(u64, u64), Error> {
+        if bytes.len() < HEADER_LEN {
+            return Err(Error::Deserialization(format!(
+                "Container is too short for a header: {} bytes",
+                bytes.len()
+            )));
+        }
+        if bytes[0..4] != MAGIC {
+            return Err(Error::InvalidMagic);
+        }
+
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion {
+                found: version,
+                expected: VERSION,
+            });
+        }
+
+        Ok((
+            u64::from_le_bytes(bytes[12..20].try_into().unwrap()),
+            u64::from_le_bytes(bytes[20..28].try_into().unwrap()),
+        ))
+    }
+
+    /// Computes the checksum of an encoded header, without its checksum field, followed by
+    /// the entry table.
+    fn checksum_table(header: &[u8], table: &[u8]) -> u64 {
+        checksum(&[&header[..28], &header[36..HEADER_LEN], table])
+    }
+}
+
+/// Entry table record describing a single serialized asset in a container.
+#[derive(Debug, Clone, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct Entry {
+    pub type_tag: String,
+    /// Version of the serialized layout of the asset type, see
+    /// [`Asset::schema_version`](crate::prelude::Asset::schema_version).
+    pub schema_version: u32,
+    pub metadata: Metadata,
+    /// Index of the bundle the entry belongs to in [`Container::bundles`].
+    pub bundle: u32,
+    pub offset: u64,
+    /// Length of the stored, possibly compressed, bytes.
+    pub len: u64,
+    /// Codec the stored bytes are compressed with.
+    pub codec: Codec,
+    /// Length of the serialized bytes before compression.
+    pub raw_len: u64,
+    /// xxh3 checksum of the stored bytes followed by the `Bytes` payloads.
+    pub checksum: u64,
+    /// Offset and length of each `Bytes` payload of the asset, aligned to 64 bytes.
+    pub blobs: Vec<(u64, u64)>,
+    /// Indices of the entries this entry refers to in [`Container::entries`].
+    pub dependencies: Vec<u32>,
+}
+
+/// Table stored at [`Header::table_offset`].
+#[derive(bincode::Encode, bincode::Decode)]
+struct Table {
+    bundles: Vec<Metadata>,
+    entries: Vec<Entry>,
+    order: Vec<u32>,
+}
+
+/// Read-only view over a compiled asset container.
+pub struct Container<'a> {
+    pub header: Header,
+    pub bundles: Vec<Metadata>,
+    pub entries: Vec<Entry>,
+    /// Indices of the entries in an order where every entry comes after its dependencies.
+    pub order: Vec<u32>,
+    binary: &'a [u8],
+}
+
+impl Container<'static> {
+    /// Reads the header and entry table of a container starting at `start` in `reader`.
+    ///
+    /// The entries are not read, so the returned container holds no entry bytes and its
+    /// accessors for them fail. They are read from `reader` with [`Source::read`] instead.
+    pub(crate) fn read_table<R: Read + Seek + ?Sized>(
+        reader: &mut R,
+        start: u64,
+    ) -> Result<Self, Error> {
+        let header = read_range(reader, start, HEADER_LEN as u64)?;
+        let (table_offset, table_len) = Header::table_range(&header)?;
+        let table = read_range(reader, position(start, table_offset)?, table_len)?;
+        Container::from_table(&header, &table, &[])
+    }
+}
+
+impl<'a> Container<'a> {
+    /// Parses the header and entry table of a container.
+    ///
+    /// The header and entry table are checked against [`Header::table_checksum`]. Entries
+    /// are only checked by [`Container::verify_entry`].
+    pub fn parse(binary: &'a [u8]) -> Result<Self, Error> {
+        let (table_offset, table_len) = Header::table_range(binary)?;
+        let table = slice(binary, table_offset, table_len).ok_or_else(|| {
+            Error::Deserialization(format!(
+                "Entry table is out of bounds, the container may be truncated ({} bytes)",
+                binary.len()
+            ))
+        })?;
+        Container::from_table(binary, table, binary)
+    }
+
+    /// Checks and decodes the header and entry table of a container, given the bytes of
+    /// its header and its table.
+    pub(crate) fn from_table(header: &[u8], table: &[u8], binary: &'a [u8]) -> Result<Self, Error> {
+        Header::table_range(header)?;
+        let expected = u64::from_le_bytes(header[28..36].try_into().unwrap());
+        let actual = Header::checksum_table(header, table);
+        if actual != expected {
+            return Err(Error::Corrupted {
+                entry: TABLE.to_string(),
+                expected,
+                actual,
+            });
+        }
+        let header = Header::from_bytes(header)?;
+
+        let (
+            Table {
+                bundles,
+                entries,
+                order,
+            },
+            _bytes_read,
+        ): (Table, usize) = bincode::decode_from_slice(table, bincode::config::standard())
+            .map_err(|e| {
+                Error::Deserialization(format!("Failed to deserialize entry table: {}", e))
+            })?;
+
+        if entries.len() != header.entry_count as usize {
+            return Err(Error::Deserialization(format!(
+                "Entry table has {} entries, header declares {}",
+                entries.len(),
+                header.entry_count
+            )));
+        }
+        if let Some(entry) = entries
+            .iter()
+            .find(|entry| entry.bundle as usize >= bundles.len())
+        {
+            return Err(Error::Deserialization(format!(
+                "Entry '{}' refers to missing bundle {}",
+                entry.metadata.name, entry.bundle
+            )));
+        }
+
+        if let Some(entry) = entries.iter().find(|entry| {
+            entry
+                .dependencies
+                .iter()
+                .any(|&dependency| dependency as usize >= entries.len())
+        }) {
+            return Err(Error::Deserialization(format!(
+                "Entry '{}' depends on a missing entry",
+                entry.metadata.name
+            )));
+        }
+        let mut seen = vec![false; entries.len()];
+        let complete = order.len() == entries.len()
+            && order.iter().all(|&index| {
+                seen.get_mut(index as usize)
+                    .is_some_and(|seen| !std::mem::replace(seen, true))
+            });
+        if !complete {
+            return Err(Error::Deserialization(
+                "Load order does not list every entry once".to_string(),
+            ));
+        }
+
+        Ok(Container {
+            header,
+            bundles,
+            entries,
+            order,
+            binary,
+        })
+    }
+
+    /// Returns the ranges of the `Bytes` payloads of an entry in the container.
+    pub fn blob_ranges(&self, entry: &Entry) -> Result<Vec<Range<usize>>, Error> {
+        entry
+            .blobs
+            .iter()
+            .map(|&(offset, len)| {
+                let start = offset as usize;
+                slice(self.binary, offset, len)
+                    .map(|bytes| start..start + bytes.len())
+                    .ok_or_else(|| {
+                        Error::Deserialization(format!(
+                            "Blob of entry '{}' is out of bounds",
+                            entry.metadata.name
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    /// Checks the stored bytes and `Bytes` payloads of an entry against [`Entry::checksum`].
+    pub fn verify_entry(&self, entry: &Entry) -> Result<(), Error> {
+        let mut parts = vec![self.entry_bytes(entry)?];
+        for range in self.blob_ranges(entry)? {
+            parts.push(&self.binary[range]);
+        }
+        verify(&entry.metadata.name, entry.checksum, &parts)
+    }
+
+    /// Returns the index of the bundle with the given name.
+    pub fn bundle_index(&self, name: &str) -> Option<u32> {
+        self.bundles
+            .iter()
+            .position(|bundle| bundle.name == name)
+            .map(|index| index as u32)
+    }
+
+    /// Returns the entries of the container in load order.
+    pub fn ordered_entries(&self) -> impl Iterator<Item = &Entry> {
+        self.order
+            .iter()
+            .map(|&index| &self.entries[index as usize])
+    }
+
+    /// Returns the entries belonging to the bundle at the given index.
+    pub fn bundle_entries(&self, bundle: u32) -> impl Iterator<Item = &Entry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.bundle == bundle)
+    }
+
+    /// Returns the bytes the container was parsed from.
+    pub(crate) fn binary(&self) -> &'a [u8] {
+        self.binary
+    }
+
+    /// Returns the serialized bytes of an entry, decompressing them if needed.
+    pub fn entry_data(&self, entry: &Entry) -> Result<Cow<'a, [u8]>, Error> {
+        let bytes = self.entry_bytes(entry)?;
+        match entry.codec {
+            Codec::None => Ok(Cow::Borrowed(bytes)),
+            codec => Ok(Cow::Owned(codec.decompress(bytes, entry.raw_len as usize)?)),
+        }
+    }
+
+    /// Returns the stored bytes of an entry, which are compressed with [`Entry::codec`].
+    pub fn entry_bytes(&self, entry: &Entry) -> Result<&'a [u8], Error> {
+        slice(self.binary, entry.offset, entry.len).ok_or_else(|| {
+            Error::Deserialization(format!(
+                "Entry '{}' of type '{}' is out of bounds",
+                entry.metadata.name, entry.type_tag
+            ))
+        })
+    }
+}
+
+/// Reader that entries are read from on demand.
+pub(crate) trait ReadSeek: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+/// Reader shared by the assets loaded from it.
+pub(crate) type SharedReader = Arc<Mutex<dyn ReadSeek>>;
+
+/// Where the entries of a loaded container are deserialized from.
+#[derive(Clone)]
+pub(crate) enum Source {
+    /// Bytes of the container from position `base` on, shared with the assets loaded from
+    /// them. `Bytes` payloads borrow from it.
+    Shared { bytes: SharedSource, base: usize },
+    /// A reader holding the container at `start`, seeked to every entry when it is read.
+    Reader { reader: SharedReader, start: u64 },
+}
+
+impl Source {
+    /// Returns a source over the whole container.
+    pub(crate) fn shared(bytes: SharedSource) -> Self {
+        Source::Shared { bytes, base: 0 }
+    }
+
+    /// Returns the bytes at `range` of the source and the `Bytes` payloads at `blobs`.
+    pub(crate) fn read(
+        &self,
+        range: Range<usize>,
+        blobs: &[Range<usize>],
+    ) -> Result<(Cow<'_, [u8]>, Vec<Bytes>), Error> {
+        match self {
+            Source::Shared { bytes, .. } => {
+                let blobs = blobs
+                    .iter()
+                    .map(|range| Bytes::from_shared(bytes.clone(), range.clone()))
+                    .collect();
+                Ok((Cow::Borrowed(&(**bytes).as_ref()[range]), blobs))
+            }
+            Source::Reader { reader, start } => {
+                let mut reader = reader.lock().unwrap_or_else(PoisonError::into_inner);
+                let mut read = |range: &Range<usize>| {
+                    let offset = position(*start, range.start as u64)?;
+                    read_range(&mut *reader, offset, range.len() as u64)
+                };
+                let bytes = read(&range)?;
+                let blobs = blobs
+                    .iter()
+                    .map(|range| read(range).map(Bytes::from))
+                    .collect::<Result<_, _>>()?;
+                Ok((Cow::Owned(bytes), blobs))
+            }
+        }
+    }
+
+    /// Returns the range of the stored bytes of an entry and the ranges of its `Bytes`
+    /// payloads in the source, to be read with [`Source::read`].
+    ///
+    /// Ranges in memory are checked against its bounds, while ranges of a reader are only
+    /// checked when they are read.
+    pub(crate) fn ranges(&self, entry: &Entry) -> Result<(Range<usize>, Vec<Range<usize>>), Error> {
+        let range = |offset: u64, len: u64| -> Option<Range<usize>> {
+            let start = usize::try_from(offset).ok()?;
+            let end = start.checked_add(usize::try_from(len).ok()?)?;
+            match self {
+                Source::Shared { bytes, base } => {
+                    let range = start.checked_sub(*base)?..end.checked_sub(*base)?;
+                    (range.end <= (**bytes).as_ref().len()).then_some(range)
+                }
+                Source::Reader { .. } => Some(start..end),
+            }
+        };
+        let bytes = range(entry.offset, entry.len).ok_or_else(|| {
+            Error::Deserialization(format!(
+                "Entry '{}' of type '{}' is out of bounds",
+                entry.metadata.name, entry.type_tag
+            ))
+        })?;
+        let blobs = entry
+            .blobs
+            .iter()
+            .map(|&(offset, len)| {
+                range(offset, len).ok_or_else(|| {
+                    Error::Deserialization(format!(
+                        "Blob of entry '{}' is out of bounds",
+                        entry.metadata.name
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok((bytes, blobs))
+    }
+}
+
+/// Reads `len` bytes at `offset` of a reader.
+fn read_range<R: Read + Seek + ?Sized>(
+    reader: &mut R,
+    offset: u64,
+    len: u64,
+) -> Result<Vec<u8>, Error> {
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(Error::Deserialization(format!(
+            "Container is truncated at offset {}",
+            offset.saturating_add(bytes.len() as u64)
+        )));
+    }
+    Ok(bytes)
+}
+
+/// Returns the position in a reader of `offset` in a container starting at `start`.
+pub(crate) fn position(start: u64, offset: u64) -> Result<u64, Error> {
+    start.checked_add(offset).ok_or_else(|| {
+        Error::Deserialization(format!(
+            "Offset {} is out of range of the container",
+            offset
+        ))
+    })
+}
+
+/// Stored form of an entry, compressed and checksummed before it is written by
+/// [`ContainerWriter::push`].
+pub(crate) struct Payload {
+    stored: Vec<u8>,
+    codec: Codec,
+    raw_len: u64,
+    checksum: u64,
+    blobs: Blobs,
+}
+
+impl Payload {
+    /// Compresses the serialized bytes of an entry with `codec` unless that does not make them
+    /// smaller. `Bytes` payloads are never compressed, so that they can be borrowed from the
+    /// container.
+    pub(crate) fn new(bytes: Vec<u8>, codec: Codec, blobs: Blobs) -> Result<Self, Error> {
+        let raw_len = bytes.len() as u64;
+        let (stored, codec) = match codec {
+            Codec::None => (bytes, Codec::None),
+            codec => {
+                let compressed = codec.compress(&bytes)?;
+                if compressed.len() < bytes.len() {
+                    (compressed, codec)
+                } else {
+                    (bytes, Codec::None)
+                }
+            }
+        };
+        let mut parts = vec![stored.as_slice()];
+        parts.extend(blobs.ranges.iter().map(|range| &blobs.data[range.clone()]));
+        let checksum = checksum(&parts);
+        Ok(Payload {
+            stored,
+            codec,
+            raw_len,
+            checksum,
+            blobs,
+        })
+    }
+}
+
+/// Builds a container by appending entry payloads and writing the table last.
+///
+/// The header is written once the table is known, by seeking back to the start of the
+/// container, so only the entry table is kept in memory.
+pub(crate) struct ContainerWriter<W> {
+    writer: W,
+    /// Position of the start of the container in `writer`.
+    start: u64,
+    /// Number of bytes written so far, including the header.
+    len: u64,
+    bundles: Vec<String>,
+    entries: Vec<Entry>,
+    /// Format the pushed payloads are serialized in.
+    format: Format,
+}
+
+impl<W: Write + Seek> ContainerWriter<W> {
+    /// Starts a container at the current position of `writer`, reserving space for the header.
+    pub(crate) fn new(mut writer: W, format: Format) -> Result<Self, Error> {
+        let start = writer.stream_position()?;
+        writer.write_all(&[0; HEADER_LEN])?;
+        Ok(ContainerWriter {
+            writer,
+            start,
+            len: HEADER_LEN as u64,
+            bundles: Vec::new(),
+            entries: Vec::new(),
+            format,
+        })
+    }
+
+    /// Starts a new bundle, returning its index for [`ContainerWriter::push`].
+    pub(crate) fn push_bundle(&mut self, name: String) -> u32 {
+        self.bundles.push(name);
+        (self.bundles.len() - 1) as u32
+    }
+
+    /// Writes bytes at the end of the container.
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.writer.write_all(bytes)?;
+        self.len += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// Appends the payload of an entry, preceded by its `Bytes` payloads, returning the index
+    /// of the entry.
+    pub(crate) fn push(
+        &mut self,
+        type_tag: String,
+        schema_version: u32,
+        bundle: u32,
+        metadata: Metadata,
+        payload: Payload,
+    ) -> Result<u32, Error> {
+        let Payload {
+            stored,
+            codec,
+            raw_len,
+            checksum,
+            blobs,
+        } = payload;
+
+        let mut ranges = Vec::with_capacity(blobs.ranges.len());
+        if !blobs.ranges.is_empty() {
+            let base = self.len.next_multiple_of(BLOB_ALIGN as u64);
+            self.write(&vec![0; (base - self.len) as usize])?;
+            self.write(&blobs.data)?;
+            ranges.extend(
+                blobs
+                    .ranges
+                    .iter()
+                    .map(|range| (base + range.start as u64, range.len() as u64)),
+            );
+        }
+
+        self.entries.push(Entry {
+            type_tag,
+            schema_version,
+            metadata,
+            bundle,
+            offset: self.len,
+            len: stored.len() as u64,
+            codec,
+            raw_len,
+            checksum,
+            blobs: ranges,
+            dependencies: Vec::new(),
+        });
+        self.write(&stored)?;
+        Ok((self.entries.len() - 1) as u32)
+    }
+
+    /// Replaces the entry at `index` with the entry pushed last.
+    ///
+    /// The payload of the replaced entry stays in the container, but is no longer referenced.
+    pub(crate) fn supersede(&mut self, index: u32) {
+        if let Some(entry) = self.entries.pop() {
+            self.entries[index as usize] = entry;
+        }
+    }
+
+    /// Writes the entry table and header, returning the writer positioned after the container.
+    ///
+    /// `dependencies` lists the dependencies of every entry and `order` the load order of the
+    /// entries. The hash of a bundle is computed from the hashes of its entries.
+    pub(crate) fn finish(
+        mut self,
+        dependencies: Vec<Vec<u32>>,
+        order: Vec<u32>,
+    ) -> Result<W, Error> {
+        for (entry, dependencies) in self.entries.iter_mut().zip(dependencies) {
+            entry.dependencies = dependencies;
+        }
+        let bundles = (0..self.bundles.len() as u32)
+            .zip(std::mem::take(&mut self.bundles))
+            .map(|(index, name)| {
+                let hashes: String = self
+                    .entries
+                    .iter()
+                    .filter(|entry| entry.bundle == index)
+                    .map(|entry| entry.metadata.hash.as_str())
+                    .collect();
+                Metadata::new(
+                    name,
+                    std::any::type_name::<Bundle>().to_string(),
+                    hashes.as_bytes(),
+                )
+            })
+            .collect();
+        let table = Table {
+            bundles,
+            entries: std::mem::take(&mut self.entries),
+            order,
+        };
+        let entry_count = table.entries.len();
+        let table = bincode::encode_to_vec(&table, bincode::config::standard())
+            .map_err(|e| Error::Serialization(format!("Failed to serialize entry table: {}", e)))?;
+
+        let mut header = Header {
+            version: VERSION,
+            flags: 0,
+            entry_count: entry_count as u32,
+            table_offset: self.len,
+            table_len: table.len() as u64,
+            table_checksum: 0,
+            format: self.format,
+        };
+        header.table_checksum = Header::checksum_table(&header.to_bytes(), &table);
+        self.write(&table)?;
+        self.writer.seek(SeekFrom::Start(self.start))?;
+        self.writer.write_all(&header.to_bytes())?;
+        self.writer.seek(SeekFrom::Start(self.start + self.len))?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+/// Name reported by [`Error::Corrupted`] when the entry table is corrupted.
+pub(crate) const TABLE: &str = "<entry table>";
+
+/// Computes the checksum of consecutive parts of an entry.
+pub(crate) fn checksum(parts: &[&[u8]]) -> u64 {
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.digest()
+}
+
+/// Checks the parts of an entry against its expected checksum.
+pub(crate) fn verify(entry: &str, expected: u64, parts: &[&[u8]]) -> Result<(), Error> {
+    let actual = checksum(parts);
+    if actual != expected {
+        return Err(Error::Corrupted {
+            entry: entry.to_string(),
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+fn slice(binary: &[u8], offset: u64, len: u64) -> Option<&[u8]> {
+    let start = usize::try_from(offset).ok()?;
+    let end = start.checked_add(usize::try_from(len).ok()?)?;
+    binary.get(start..end)
+}
@@ -14,4 +14,80 @@ pub enum Error {
 
     #[error("Asset type not found: {0}")]
     TypeNotFound(String),
+
+    #[error("Duplicate asset '{name}' of type {type_name}")]
+    DuplicateAsset { type_name: String, name: String },
+
+    #[error("Asset type id '{type_tag}' is used by both {first} and {second}")]
+    TypeTagCollision {
+        type_tag: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("Asset type name '{name}' matches both {first} and {second}, use its type tag")]
+    AmbiguousType {
+        name: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("Asset '{asset}' refers to missing {type_name} '{name}'")]
+    DanglingReference {
+        asset: String,
+        type_name: String,
+        name: String,
+    },
+
+    #[error("Dependency cycle between assets: {}", .0.join(" -> "))]
+    DependencyCycle(Vec<String>),
+
+    #[error("Invalid asset '{asset}' of type {type_name}: {reason}")]
+    InvalidAsset {
+        asset: String,
+        type_name: String,
+        reason: String,
+    },
+
+    #[error(
+        "{} assets failed validation: {}",
+        .0.len(),
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    Validation(Vec<Error>),
+
+    #[error(
+        "No migration for schema version {found} of {type_name}, current version is {expected}"
+    )]
+    MissingMigration {
+        type_name: String,
+        found: u32,
+        expected: u32,
+    },
+
+    #[error("Bundle not found: {0}")]
+    BundleNotFound(String),
+
+    #[error("Bundle '{0}' is already loaded")]
+    BundleLoaded(String),
+
+    #[error("Not a rasset container: invalid magic bytes")]
+    InvalidMagic,
+
+    #[error("Unsupported container version {found}, expected {expected}")]
+    UnsupportedVersion { found: u16, expected: u16 },
+
+    #[error("Corrupted entry '{entry}': expected checksum {expected:016x}, found {actual:016x}")]
+    Corrupted {
+        /// Name of the corrupted entry, or `<entry table>` for the entry table.
+        entry: String,
+        expected: u64,
+        actual: u64,
+    },
+
+    #[error("Codec {0:?} is not enabled, enable its cargo feature")]
+    CodecUnavailable(crate::codec::Codec),
+
+    #[error("Format {0:?} is not enabled, enable its cargo feature")]
+    FormatUnavailable(crate::format::Format),
 }
@@ -0,0 +1,86 @@
+use crate::{error::Error, prelude::bincode};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Serialization format of the entries of a compiled container, recorded in its header.
+///
+/// Bincode is compact and supports borrowing `Bytes` payloads from the container. The other
+/// formats are self-describing, so entries can be inspected or read by tools in other
+/// languages, and are written through serde. Every format is part of the container format,
+/// but using one other than bincode requires its cargo feature (`msgpack`, `cbor` or `json`).
+/// Otherwise [`Error::FormatUnavailable`] is returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Format {
+    #[default]
+    Bincode,
+    MessagePack,
+    Cbor,
+    Json,
+}
+
+impl Format {
+    /// Returns the identifier of the format stored in [`Header`](crate::prelude::Header).
+    pub fn id(self) -> u16 {
+        match self {
+            Format::Bincode => 0,
+            Format::MessagePack => 1,
+            Format::Cbor => 2,
+            Format::Json => 3,
+        }
+    }
+
+    /// Returns the format with the given identifier.
+    pub fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(Format::Bincode),
+            1 => Some(Format::MessagePack),
+            2 => Some(Format::Cbor),
+            3 => Some(Format::Json),
+            _ => None,
+        }
+    }
+
+    /// Serializes a value with serde in this format.
+    pub fn serialize<T: Serialize + ?Sized>(self, value: &T) -> Result<Vec<u8>, Error> {
+        let error = |e: &dyn std::fmt::Display| {
+            Error::Serialization(format!("Failed to serialize as {:?}: {}", self, e))
+        };
+        match self {
+            Format::Bincode => bincode::serde::encode_to_vec(value, bincode::config::standard())
+                .map_err(|e| error(&e)),
+            #[cfg(feature = "msgpack")]
+            Format::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| error(&e)),
+            #[cfg(feature = "cbor")]
+            Format::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes).map_err(|e| error(&e))?;
+                Ok(bytes)
+            }
+            #[cfg(feature = "json")]
+            Format::Json => serde_json::to_vec_pretty(value).map_err(|e| error(&e)),
+            #[allow(unreachable_patterns)]
+            format => Err(Error::FormatUnavailable(format)),
+        }
+    }
+
+    /// Deserializes a value with serde from bytes in this format.
+    pub fn deserialize<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, Error> {
+        let error = |e: &dyn std::fmt::Display| {
+            Error::Deserialization(format!("Failed to deserialize {:?}: {}", self, e))
+        };
+        match self {
+            Format::Bincode => {
+                bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+                    .map(|(value, _)| value)
+                    .map_err(|e| error(&e))
+            }
+            #[cfg(feature = "msgpack")]
+            Format::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| error(&e)),
+            #[cfg(feature = "cbor")]
+            Format::Cbor => ciborium::from_reader(bytes).map_err(|e| error(&e)),
+            #[cfg(feature = "json")]
+            Format::Json => serde_json::from_slice(bytes).map_err(|e| error(&e)),
+            #[allow(unreachable_patterns)]
+            format => Err(Error::FormatUnavailable(format)),
+        }
+    }
+}
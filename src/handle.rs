@@ -0,0 +1,56 @@
+use std::{fmt, hash, marker::PhantomData};
+
+/// Copyable reference to an asset of type `T` in a [`Registry`](crate::prelude::Registry).
+///
+/// A handle is a generational index: it stays valid while its asset is loaded, including
+/// across reloads that change the asset, and becomes stale once the asset is removed, for
+/// example by unloading its bundle. Stale handles resolve to `None`, even if their slot was
+/// reused by another asset.
+pub struct Handle<T> {
+    pub(crate) index: u32,
+    pub(crate) generation: u32,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    pub(crate) fn new(index: u32, generation: u32) -> Self {
+        Handle {
+            index,
+            generation,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> hash::Hash for Handle<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handle")
+            .field("type", &std::any::type_name::<T>())
+            .field("index", &self.index)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
@@ -1,21 +1,51 @@
 mod asset;
+mod blob;
 mod bundle;
+mod bytes;
+mod codec;
 mod compiler;
+mod container;
 mod error;
+mod format;
+mod handle;
+#[cfg(feature = "async")]
+mod loader;
 mod metadata;
+mod policy;
+mod raw;
+mod reference;
 mod registry;
+#[cfg(feature = "ron")]
+mod ron_loader;
 mod r#type;
+mod validation;
+#[cfg(feature = "hot-reload")]
+mod watch;
 
 pub mod prelude {
     pub use rasset_macros::*;
 
     pub use super::asset::Asset;
     pub use super::bundle::Bundle;
-    pub use super::compiler::Compiler;
+    pub use super::bytes::{Bytes, SharedSource};
+    pub use super::codec::Codec;
+    pub use super::compiler::{Compiler, CompilerWriter};
+    pub use super::container::{Container, Entry, Header};
     pub use super::error::Error;
+    pub use super::format::Format;
+    pub use super::handle::Handle;
+    #[cfg(feature = "async")]
+    pub use super::loader::{AsyncLoader, LoadProgress};
     pub use super::metadata::Metadata;
-    pub use super::registry::Registry;
+    pub use super::policy::{DuplicatePolicy, LoadMode, UnknownTypePolicy};
+    pub use super::raw::RawAsset;
+    pub use super::reference::{AssetRef, CollectRefs, Reference, ReferenceNames};
+    pub use super::registry::{
+        AssetEvent, AssetEventKind, LoadFailure, LoadReport, Migrations, Registry, UnloadReport,
+    };
     pub use super::r#type::Type;
+    pub use super::validation::{InRange, ValidationContext, ValidationStage};
 
     pub use bincode;
+    pub use serde;
 }
@@ -0,0 +1,309 @@
+//! Asynchronous loading of compiled containers from `futures::io` readers.
+
+use crate::{
+    asset::Asset,
+    container::{self, Container, Entry, HEADER_LEN, Header, Source},
+    error::Error,
+    metadata::Metadata,
+    registry::{BundleLoad, Registry, RegistryBuilder},
+};
+use futures::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, SeekFrom};
+use std::{
+    any::Any,
+    future::Future,
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Context, Poll},
+};
+
+/// Progress of the current load of an [`AsyncLoader`], shared between clones.
+///
+/// Every load of the loader starts over with the entries it reads as the totals, so a loading
+/// screen can poll it from another task while a bundle is loading.
+#[derive(Debug, Clone, Default)]
+pub struct LoadProgress(Arc<Counters>);
+
+#[derive(Debug, Default)]
+struct Counters {
+    entries_done: AtomicU64,
+    entries_total: AtomicU64,
+    bytes_done: AtomicU64,
+    bytes_total: AtomicU64,
+}
+
+impl LoadProgress {
+    /// Returns the number of entries loaded so far.
+    pub fn entries_done(&self) -> u64 {
+        self.0.entries_done.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of entries of the current load.
+    pub fn entries_total(&self) -> u64 {
+        self.0.entries_total.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of bytes read so far, including `Bytes` payloads.
+    pub fn bytes_done(&self) -> u64 {
+        self.0.bytes_done.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of bytes of the entries of the current load.
+    pub fn bytes_total(&self) -> u64 {
+        self.0.bytes_total.load(Ordering::Relaxed)
+    }
+
+    /// Returns the fraction of bytes read, from 0 to 1. A load without any bytes is complete.
+    pub fn fraction(&self) -> f32 {
+        match self.bytes_total() {
+            0 => 1.0,
+            total => self.bytes_done() as f32 / total as f32,
+        }
+    }
+
+    /// Returns whether all entries of the current load are loaded.
+    pub fn is_complete(&self) -> bool {
+        self.entries_done() >= self.entries_total()
+    }
+
+    /// Starts a new load of the given entries.
+    fn start<'a>(&self, entries: impl Iterator<Item = &'a Entry>) {
+        let (count, bytes) = entries.fold((0, 0), |(count, bytes), entry| {
+            (count + 1, bytes + stored_len(entry))
+        });
+        self.0.entries_done.store(0, Ordering::Relaxed);
+        self.0.bytes_done.store(0, Ordering::Relaxed);
+        self.0.entries_total.store(count, Ordering::Relaxed);
+        self.0.bytes_total.store(bytes, Ordering::Relaxed);
+    }
+
+    /// Records an entry as loaded.
+    fn advance(&self, entry: &Entry) {
+        self.0
+            .bytes_done
+            .fetch_add(stored_len(entry), Ordering::Relaxed);
+        self.0.entries_done.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns the number of bytes stored for an entry, including its `Bytes` payloads.
+fn stored_len(entry: &Entry) -> u64 {
+    entry.len + entry.blobs.iter().map(|&(_, len)| len).sum::<u64>()
+}
+
+/// Container read from an asynchronous reader, created by `RegistryBuilder::open_async`.
+///
+/// Only the header and entry table are read when it is opened. Bundles are loaded into its
+/// registry and single assets are read on request, seeking to their entries. Loading yields
+/// to the executor after every entry, so other tasks keep running while a bundle loads.
+pub struct AsyncLoader<R> {
+    reader: R,
+    /// Position of the start of the container in `reader`.
+    start: u64,
+    container: Container<'static>,
+    registry: Registry,
+    progress: LoadProgress,
+}
+
+impl RegistryBuilder {
+    /// Reads the header and entry table of a container starting at the current position of
+    /// `reader`, returning a loader for its bundles and assets.
+    pub async fn open_async<R: AsyncRead + AsyncSeek + Unpin>(
+        self,
+        mut reader: R,
+    ) -> Result<AsyncLoader<R>, Error> {
+        let registry = self.build()?;
+        let start = reader.stream_position().await?;
+        let header = read_range(&mut reader, start, HEADER_LEN as u64).await?;
+        let (table_offset, table_len) = Header::table_range(&header)?;
+        let table_start = container::position(start, table_offset)?;
+        let table = read_range(&mut reader, table_start, table_len).await?;
+        Ok(AsyncLoader {
+            reader,
+            start,
+            container: Container::from_table(&header, &table, &[])?,
+            registry,
+            progress: LoadProgress::default(),
+        })
+    }
+
+    /// Loads all bundles of a container from an asynchronous reader, see
+    /// [`AsyncLoader::load_all`].
+    pub async fn load_async<R: AsyncRead + AsyncSeek + Unpin>(
+        self,
+        reader: R,
+    ) -> Result<Registry, Error> {
+        let mut loader = self.open_async(reader).await?;
+        loader.load_all().await?;
+        Ok(loader.into_registry())
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncLoader<R> {
+    /// Returns the progress of the loads of this loader.
+    pub fn progress(&self) -> LoadProgress {
+        self.progress.clone()
+    }
+
+    /// Returns the metadata of the bundles of the container.
+    pub fn bundles(&self) -> impl Iterator<Item = &Metadata> {
+        self.container.bundles.iter()
+    }
+
+    /// Returns the registry the bundles are loaded into.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Returns the registry, keeping the assets that were loaded.
+    pub fn into_registry(self) -> Registry {
+        self.registry
+    }
+
+    /// Loads the bundle with the given name into the registry.
+    ///
+    /// Fails with [`Error::BundleLoaded`] if it is already loaded. In [`LoadMode::Lazy`]
+    /// the entries are read, but only deserialized on first access. The bundle is only added
+    /// once its load hooks succeed, see [`Registry::load_bundle`].
+    ///
+    /// [`LoadMode::Lazy`]: crate::prelude::LoadMode::Lazy
+    pub async fn load_bundle(&mut self, name: &str) -> Result<(), Error> {
+        let bundle = self
+            .container
+            .bundle_index(name)
+            .ok_or_else(|| Error::BundleNotFound(name.to_string()))?;
+        if self.registry.has_bundle(name) {
+            return Err(Error::BundleLoaded(name.to_string()));
+        }
+        self.load_bundles(&[bundle]).await
+    }
+
+    /// Loads all bundles of the container that are not loaded yet into the registry.
+    pub async fn load_all(&mut self) -> Result<(), Error> {
+        let bundles: Vec<u32> = (0..self.container.bundles.len() as u32)
+            .filter(|&bundle| {
+                let name = &self.container.bundles[bundle as usize].name;
+                !self.registry.has_bundle(name)
+            })
+            .collect();
+        self.load_bundles(&bundles).await
+    }
+
+    /// Reads a single asset of the container without loading its bundle.
+    ///
+    /// Returns the asset from the registry if its bundle is loaded, and `None` if the
+    /// container has no asset of type `T` with the given name.
+    pub async fn load_asset<T: Asset + 'static>(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<Arc<T>>, Error> {
+        if let Some(asset) = self.registry.try_get_arc::<T>(name)? {
+            return Ok(Some(asset));
+        }
+        let Some(index) = self.container.entries.iter().position(|entry| {
+            entry.type_tag == T::static_type_tag() && entry.metadata.name == name
+        }) else {
+            return Ok(None);
+        };
+
+        let entry = &self.container.entries[index];
+        self.progress.start(std::iter::once(entry));
+        let source = self.read_entry(index).await?;
+        let entry = &self.container.entries[index];
+        let asset: Box<dyn Any + Send + Sync> =
+            self.registry
+                .deserialize_entry(&self.container, entry, &source)?;
+        self.progress.advance(entry);
+        Ok(asset.downcast::<T>().ok().map(Arc::from))
+    }
+
+    /// Loads the bundles at the given indices, one entry at a time.
+    async fn load_bundles(&mut self, bundles: &[u32]) -> Result<(), Error> {
+        let container = &self.container;
+        self.progress.start(
+            container
+                .entries
+                .iter()
+                .filter(|entry| bundles.contains(&entry.bundle)),
+        );
+        for &bundle in bundles {
+            let mut next = self.registry.staging();
+            let mut load = BundleLoad::new(&self.container, bundle);
+            for index in 0..self.container.entries.len() {
+                if self.container.entries[index].bundle != bundle {
+                    continue;
+                }
+                let source = self.read_entry(index).await?;
+                next.load_into(&mut load, &self.container, index, Some(&source), None)?;
+                self.progress.advance(&self.container.entries[index]);
+                YieldNow(false).await;
+            }
+            next.finish_bundle(load, true)?;
+            self.registry.commit_bundle(next, &self.container, bundle)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the stored bytes of the entry at `index` together with its `Bytes` payloads,
+    /// which are stored right before them.
+    async fn read_entry(&mut self, index: usize) -> Result<Source, Error> {
+        let entry = &self.container.entries[index];
+        let base = entry
+            .blobs
+            .iter()
+            .fold(entry.offset, |base, &(offset, _)| base.min(offset));
+        let out_of_bounds = || {
+            Error::Deserialization(format!(
+                "Entry '{}' of type '{}' is out of bounds",
+                entry.metadata.name, entry.type_tag
+            ))
+        };
+        let end = entry
+            .offset
+            .checked_add(entry.len)
+            .ok_or_else(out_of_bounds)?;
+        let base_index = usize::try_from(base).map_err(|_| out_of_bounds())?;
+        let offset = container::position(self.start, base)?;
+        let bytes = read_range(&mut self.reader, offset, end - base).await?;
+        Ok(Source::Shared {
+            bytes: Arc::new(bytes),
+            base: base_index,
+        })
+    }
+}
+
+/// Reads `len` bytes at `offset` of a reader.
+async fn read_range<R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+    offset: u64,
+    len: u64,
+) -> Result<Vec<u8>, Error> {
+    reader.seek(SeekFrom::Start(offset)).await?;
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes).await?;
+    if bytes.len() as u64 != len {
+        return Err(Error::Deserialization(format!(
+            "Container is truncated at offset {}",
+            offset.saturating_add(bytes.len() as u64)
+        )));
+    }
+    Ok(bytes)
+}
+
+/// Future that returns to the executor once before it completes.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
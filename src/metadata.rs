@@ -1,7 +1,27 @@
 /// Struct representing metadata for an asset.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, bincode::Encode, bincode::Decode)]
 pub struct Metadata {
     pub name: String,
     pub type_name: String,
     pub hash: String,
 }
+
+impl Metadata {
+    /// Creates metadata for an asset, hashing its serialized bytes.
+    pub fn new(name: String, type_name: String, bytes: &[u8]) -> Self {
+        Self::from_parts(name, type_name, &[bytes])
+    }
+
+    /// Creates metadata for an asset whose serialized data is split into several parts.
+    pub(crate) fn from_parts(name: String, type_name: String, parts: &[&[u8]]) -> Self {
+        let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        Metadata {
+            name,
+            type_name,
+            hash: format!("{:016x}", hasher.digest()),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+/// Determines what happens when two assets share both a type and a name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Fail with [`Error::DuplicateAsset`](crate::prelude::Error::DuplicateAsset).
+    #[default]
+    Error,
+    /// Keep the asset that was added first and ignore later ones.
+    FirstWins,
+    /// Replace the earlier asset with the one added last.
+    LastWins,
+}
+
+/// Determines when the assets of a container are deserialized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoadMode {
+    /// Deserialize every asset while loading.
+    #[default]
+    Eager,
+    /// Only read the entry table while loading and deserialize each asset on first access.
+    Lazy,
+}
+
+/// Determines what happens to assets whose type is not registered in the
+/// [`Registry`](crate::prelude::Registry).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownTypePolicy {
+    /// Fail with [`Error::TypeNotFound`](crate::prelude::Error::TypeNotFound).
+    #[default]
+    Error,
+    /// Leave the asset out, listing it in
+    /// [`Registry::skipped`](crate::prelude::Registry::skipped).
+    Skip,
+    /// Keep the asset as a [`RawAsset`](crate::prelude::RawAsset), listed in
+    /// [`Registry::raw_assets`](crate::prelude::Registry::raw_assets).
+    Raw,
+}
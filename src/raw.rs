@@ -0,0 +1,98 @@
+use crate::{
+    blob,
+    bytes::Bytes,
+    codec::Codec,
+    container::{self, Container, Entry, Source},
+    error::Error,
+    format::Format,
+};
+use std::borrow::Cow;
+
+/// An asset of a type that is not registered, kept in its serialized form.
+///
+/// Loaded by a [`Registry`](crate::prelude::Registry) under
+/// [`UnknownTypePolicy::Raw`](crate::prelude::UnknownTypePolicy::Raw) and written back
+/// unchanged by [`Compiler::add_raw`](crate::prelude::Compiler::add_raw).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawAsset {
+    /// Stable identifier of the asset type, see [`Asset::type_tag`](crate::prelude::Asset::type_tag).
+    pub type_tag: String,
+    /// Version of the serialized layout the asset was compiled with.
+    pub schema_version: u32,
+    /// Type name the asset was compiled with.
+    pub type_name: String,
+    pub name: String,
+    /// Format `bytes` is serialized in.
+    pub format: Format,
+    /// Serialized asset, uncompressed.
+    pub bytes: Vec<u8>,
+    /// `Bytes` payloads of the asset, referred to by index from `bytes`.
+    pub blobs: Vec<Vec<u8>>,
+    /// Type tags and names of the assets this asset depends on.
+    pub dependencies: Vec<(String, String)>,
+}
+
+impl RawAsset {
+    /// Copies an entry of a parsed container, verifying it first if `verify` is set.
+    ///
+    /// The entry is read from `source` if given, otherwise from the parsed bytes.
+    pub(crate) fn from_entry(
+        container: &Container,
+        entry: &Entry,
+        source: Option<&Source>,
+        verify: bool,
+    ) -> Result<Self, Error> {
+        let (bytes, blobs) = match source {
+            Some(source) => {
+                let (range, blobs) = source.ranges(entry)?;
+                source.read(range, &blobs)?
+            }
+            None => (
+                Cow::Borrowed(container.entry_bytes(entry)?),
+                container
+                    .blob_ranges(entry)?
+                    .into_iter()
+                    .map(|range| Bytes::from(&container.binary()[range]))
+                    .collect(),
+            ),
+        };
+        if verify {
+            let mut parts = vec![bytes.as_ref()];
+            parts.extend(blobs.iter().map(|blob| blob.as_ref()));
+            container::verify(&entry.metadata.name, entry.checksum, &parts)?;
+        }
+        let bytes = match entry.codec {
+            Codec::None => bytes.into_owned(),
+            codec => codec.decompress(&bytes, entry.raw_len as usize)?,
+        };
+        Ok(RawAsset {
+            type_tag: entry.type_tag.clone(),
+            schema_version: entry.schema_version,
+            type_name: entry.metadata.type_name.clone(),
+            name: entry.metadata.name.clone(),
+            format: container.header.format,
+            bytes,
+            blobs: blobs.iter().map(|blob| blob.to_vec()).collect(),
+            dependencies: entry
+                .dependencies
+                .iter()
+                .map(|&index| {
+                    let dependency = &container.entries[index as usize];
+                    (
+                        dependency.type_tag.clone(),
+                        dependency.metadata.name.clone(),
+                    )
+                })
+                .collect(),
+        })
+    }
+
+    /// Returns the payloads in the layout they are stored in next to the entry.
+    pub(crate) fn collect_blobs(&self) -> blob::Blobs {
+        let mut blobs = blob::Blobs::default();
+        for payload in &self.blobs {
+            blobs.append(payload);
+        }
+        blobs
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{asset::Asset, handle::Handle, prelude::bincode, registry::Registry, r#type::Type};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, hash,
+    marker::PhantomData,
+    sync::Arc,
+};
+
+/// Reference from one asset to another asset of type `T`, stored as the target's name.
+///
+/// References are checked by [`Compiler::compile`](crate::prelude::Compiler::compile), which
+/// fails with [`Error::DanglingReference`](crate::prelude::Error::DanglingReference) if the
+/// target is not compiled with the referencing asset.
+pub struct AssetRef<T> {
+    name: String,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Asset> AssetRef<T> {
+    /// Creates a reference to the asset of type `T` with the given name.
+    pub fn new(name: impl Into<String>) -> Self {
+        AssetRef {
+            name: name.into(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the name of the referenced asset.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the referenced asset, or `None` if it is not loaded.
+    pub fn resolve<'a>(&self, registry: &'a Registry) -> Option<&'a T> {
+        registry.get_asset(&self.name)
+    }
+
+    /// Returns a shared reference to the referenced asset, or `None` if it is not loaded.
+    pub fn resolve_arc(&self, registry: &Registry) -> Option<Arc<T>> {
+        registry.get_arc(&self.name)
+    }
+
+    /// Returns a handle to the referenced asset, or `None` if it is not loaded.
+    pub fn handle(&self, registry: &Registry) -> Option<Handle<T>> {
+        registry.handle(&self.name)
+    }
+}
+
+impl<T: Asset> From<&str> for AssetRef<T> {
+    fn from(name: &str) -> Self {
+        AssetRef::new(name)
+    }
+}
+
+impl<T: Asset> From<String> for AssetRef<T> {
+    fn from(name: String) -> Self {
+        AssetRef::new(name)
+    }
+}
+
+impl<T> Clone for AssetRef<T> {
+    fn clone(&self) -> Self {
+        AssetRef {
+            name: self.name.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> PartialEq for AssetRef<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl<T> Eq for AssetRef<T> {}
+
+impl<T> hash::Hash for AssetRef<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for AssetRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AssetRef").field(&self.name).finish()
+    }
+}
+
+impl<T> bincode::Encode for AssetRef<T> {
+    fn encode<E: bincode::enc::Encoder>(
+        &self,
+        encoder: &mut E,
+    ) -> Result<(), bincode::error::EncodeError> {
+        self.name.encode(encoder)
+    }
+}
+
+impl<T, Context> bincode::Decode<Context> for AssetRef<T> {
+    fn decode<D: bincode::de::Decoder<Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        Ok(AssetRef {
+            name: String::decode(decoder)?,
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<'de, T, Context> bincode::BorrowDecode<'de, Context> for AssetRef<T> {
+    fn borrow_decode<D: bincode::de::BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        bincode::Decode::decode(decoder)
+    }
+}
+
+impl<T> serde::Serialize for AssetRef<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name)
+    }
+}
+
+impl<'de, T> serde::Deserialize<'de> for AssetRef<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(AssetRef {
+            name: String::deserialize(deserializer)?,
+            marker: PhantomData,
+        })
+    }
+}
+
+/// Target of a reference returned by [`Asset::references`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Reference {
+    pub r#type: Type,
+    pub type_name: &'static str,
+    pub name: String,
+}
+
+impl Reference {
+    /// Creates a reference to the asset of type `T` with the given name.
+    pub fn to<T: Asset>(name: &str) -> Self {
+        Reference {
+            r#type: Type(std::any::TypeId::of::<T>()),
+            type_name: std::any::type_name::<T>(),
+            name: name.to_string(),
+        }
+    }
+}
+
+/// Collects the [`AssetRef`]s of a field, implemented for references and collections of them.
+///
+/// `asset_def!` calls it for every field whose type contains `AssetRef`.
+pub trait CollectRefs {
+    fn collect_refs(&self, refs: &mut Vec<Reference>);
+}
+
+impl<T: Asset> CollectRefs for AssetRef<T> {
+    fn collect_refs(&self, refs: &mut Vec<Reference>) {
+        refs.push(Reference::to::<T>(&self.name));
+    }
+}
+
+impl<C: CollectRefs> CollectRefs for Option<C> {
+    fn collect_refs(&self, refs: &mut Vec<Reference>) {
+        if let Some(value) = self {
+            value.collect_refs(refs);
+        }
+    }
+}
+
+impl<C: CollectRefs> CollectRefs for Box<C> {
+    fn collect_refs(&self, refs: &mut Vec<Reference>) {
+        (**self).collect_refs(refs);
+    }
+}
+
+impl<C: CollectRefs> CollectRefs for Vec<C> {
+    fn collect_refs(&self, refs: &mut Vec<Reference>) {
+        for value in self {
+            value.collect_refs(refs);
+        }
+    }
+}
+
+impl<C: CollectRefs, const N: usize> CollectRefs for [C; N] {
+    fn collect_refs(&self, refs: &mut Vec<Reference>) {
+        for value in self {
+            value.collect_refs(refs);
+        }
+    }
+}
+
+impl<K, C: CollectRefs, S> CollectRefs for HashMap<K, C, S> {
+    fn collect_refs(&self, refs: &mut Vec<Reference>) {
+        for value in self.values() {
+            value.collect_refs(refs);
+        }
+    }
+}
+
+impl<K, C: CollectRefs> CollectRefs for BTreeMap<K, C> {
+    fn collect_refs(&self, refs: &mut Vec<Reference>) {
+        for value in self.values() {
+            value.collect_refs(refs);
+        }
+    }
+}
+
+/// Lists the asset names stored in a field marked with `#[reference(T)]` in `asset_def!`,
+/// implemented for strings and collections of them.
+pub trait ReferenceNames {
+    fn reference_names(&self, names: &mut dyn FnMut(&str));
+}
+
+impl ReferenceNames for String {
+    fn reference_names(&self, names: &mut dyn FnMut(&str)) {
+        names(self);
+    }
+}
+
+impl<N: ReferenceNames> ReferenceNames for Option<N> {
+    fn reference_names(&self, names: &mut dyn FnMut(&str)) {
+        if let Some(value) = self {
+            value.reference_names(names);
+        }
+    }
+}
+
+impl<N: ReferenceNames> ReferenceNames for Box<N> {
+    fn reference_names(&self, names: &mut dyn FnMut(&str)) {
+        (**self).reference_names(names);
+    }
+}
+
+impl<N: ReferenceNames> ReferenceNames for Vec<N> {
+    fn reference_names(&self, names: &mut dyn FnMut(&str)) {
+        for value in self {
+            value.reference_names(names);
+        }
+    }
+}
+
+impl<N: ReferenceNames, const M: usize> ReferenceNames for [N; M] {
+    fn reference_names(&self, names: &mut dyn FnMut(&str)) {
+        for value in self {
+            value.reference_names(names);
+        }
+    }
+}
+
+impl<K, N: ReferenceNames, S> ReferenceNames for HashMap<K, N, S> {
+    fn reference_names(&self, names: &mut dyn FnMut(&str)) {
+        for value in self.values() {
+            value.reference_names(names);
+        }
+    }
+}
+
+impl<K, N: ReferenceNames> ReferenceNames for BTreeMap<K, N> {
+    fn reference_names(&self, names: &mut dyn FnMut(&str)) {
+        for value in self.values() {
+            value.reference_names(names);
+        }
+    }
+}
@@ -1,9 +1,35 @@
-use crate::{asset::Asset, error::Error};
-use std::collections::HashMap;
+use crate::{
+    asset::Asset,
+    blob,
+    bytes::Bytes,
+    codec::Codec,
+    container::{self, Container, Entry, Source},
+    error::Error,
+    format::Format,
+    handle::Handle,
+    metadata::Metadata,
+    policy::{DuplicatePolicy, LoadMode, UnknownTypePolicy},
+    prelude::bincode,
+    raw::RawAsset,
+    r#type::Type,
+    validation::{self, ValidationStage},
+};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    io::{Read, Seek},
+    marker::PhantomData,
+    ops::Range,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 /// RegistryBuilder is used to build a registry of assets with their respective types.
 pub struct RegistryBuilder {
     registry: Registry,
+    error: Option<Error>,
 }
 
 impl Default for RegistryBuilder {
@@ -17,66 +43,1363 @@ impl RegistryBuilder {
     pub fn new() -> Self {
         RegistryBuilder {
             registry: Registry::new(),
+            error: None,
         }
     }
 
     /// Registers an asset type in the registry.
+    ///
+    /// Registering two types with the same type tag is reported by [`RegistryBuilder::load`].
     pub fn reg_type<T>(mut self) -> Self
     where
         T: Asset + 'static,
     {
-        self.registry.reg_type::<T>();
+        if let Err(error) = self.registry.reg_type::<T>() {
+            self.error.get_or_insert(error);
+        }
+        self
+    }
+
+    /// Registers an asset type and returns a builder for migrations from its older schema
+    /// versions.
+    ///
+    /// ```ignore
+    /// Registry::builder()
+    ///     .reg_type_with_migrations::<Sprite>()
+    ///     .migrate(1, |old: SpriteV1| Sprite { /* ... */ })
+    ///     .done()
+    ///     .load(&binary)?;
+    /// ```
+    pub fn reg_type_with_migrations<T>(self) -> Migrations<T>
+    where
+        T: Asset + 'static,
+    {
+        Migrations {
+            builder: self.reg_type::<T>(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Sets how assets with the same type and name are handled when loading or merging.
+    pub fn with_duplicate_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.registry.duplicates = policy;
+        self
+    }
+
+    /// Sets how assets of types that were not registered with [`RegistryBuilder::reg_type`]
+    /// are handled while loading.
+    pub fn with_unknown_type_policy(mut self, policy: UnknownTypePolicy) -> Self {
+        self.registry.unknown_types = policy;
+        self
+    }
+
+    /// Sets when assets are deserialized, see [`LoadMode`].
+    pub fn with_load_mode(mut self, mode: LoadMode) -> Self {
+        self.registry.mode = mode;
+        self
+    }
+
+    /// Sets whether entries are checked against their checksums before they are deserialized.
+    ///
+    /// Verification is enabled by default. Disabling it skips hashing the payloads of trusted
+    /// containers, while the small entry table is still checked.
+    pub fn with_verification(mut self, verify: bool) -> Self {
+        self.registry.verify = verify;
+        self
+    }
+
+    /// Sets whether assets are checked with [`Asset::validate`] after they are deserialized.
+    ///
+    /// Validation is enabled by default. Loading a container or bundle fails with
+    /// [`Error::Validation`] listing all invalid assets, while in [`LoadMode::Lazy`] an invalid
+    /// asset fails when it is first accessed.
+    pub fn with_validation(mut self, validate: bool) -> Self {
+        self.registry.validate = validate;
+        self
+    }
+
+    /// Adds a hook that runs for every loaded asset of type `T`.
+    ///
+    /// Hooks run after a container or bundle is loaded, in the order stored by
+    /// [`Compiler::compile`](crate::prelude::Compiler::compile), so the assets an asset refers
+    /// to are handled before it. An error from a hook is returned by the load, see
+    /// [`Registry::reload`] and [`Registry::load_bundle`] for the assets they keep. In
+    /// [`LoadMode::Lazy`] running a hook deserializes the asset.
+    pub fn on_load<T, F>(mut self, hook: F) -> Self
+    where
+        T: Asset + 'static,
+        F: Fn(&T, &Registry) -> Result<(), Error> + Send + Sync + 'static,
+    {
+        self.registry
+            .hooks
+            .entry(Type(std::any::TypeId::of::<T>()))
+            .or_default()
+            .push(Arc::new(move |asset, registry| {
+                match asset.as_any().downcast_ref::<T>() {
+                    Some(asset) => hook(asset, registry),
+                    None => Ok(()),
+                }
+            }));
         self
     }
 
-    /// Loads assets from a binary slice into the registry.
+    /// Finishes the registry without loading any assets.
+    ///
+    /// Bundles can be added later with [`Registry::load_bundle`] or [`Registry::reload`].
+    pub fn build(self) -> Result<Registry, Error> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.registry),
+        }
+    }
+
+    /// Loads all bundles of a compiled container into the registry.
+    ///
+    /// In [`LoadMode::Lazy`] only the entry table is read and the binary is copied once to
+    /// be deserialized from later. Entries are verified when they are deserialized, failing
+    /// with [`Error::Corrupted`] if their checksum does not match.
     pub fn load(mut self, binary: &[u8]) -> Result<Registry, Error> {
-        let (assets_with_types, _bytes_read): (Vec<(String, Vec<u8>)>, usize) =
-            bincode::decode_from_slice(binary, bincode::config::standard()).map_err(|e| {
-                Error::Deserialization(format!("Failed to deserialize assets: {}", e))
-            })?;
-
-        for (type_name, bytes) in assets_with_types {
-            if let Some(deserializer) = self.registry.deserializers.get(&type_name) {
-                let asset = deserializer(&bytes)?;
-                self.registry.assets.push(asset);
-            } else {
-                return Err(Error::Deserialization(format!(
-                    "No deserializer registered for asset type '{}'",
-                    type_name
-                )));
-            }
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let container = Container::parse(binary)?;
+        let shared = self.registry.share(binary);
+        for bundle in 0..container.bundles.len() as u32 {
+            self.registry
+                .load_from_container(&container, bundle, shared.as_ref(), None)?;
+        }
+        self.registry.run_hooks(&container, None, None)?;
+
+        Ok(self.registry)
+    }
+
+    /// Loads all bundles of a compiled container, skipping entries that fail instead of
+    /// returning the first error.
+    ///
+    /// Unknown types, corrupted entries, entries that fail to deserialize or validate,
+    /// duplicates under [`DuplicatePolicy::Error`] and assets whose load hooks fail are listed
+    /// in the returned [`LoadReport`], and all other assets are loaded. Errors that affect the
+    /// whole container, such as a corrupted entry table, still fail the load. In
+    /// [`LoadMode::Lazy`] only failures found without deserializing are reported, apart from
+    /// assets deserialized to run their hooks.
+    pub fn load_with_report(mut self, binary: &[u8]) -> Result<(Registry, LoadReport), Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let container = Container::parse(binary)?;
+        let shared = self.registry.share(binary);
+        let mut report = LoadReport::default();
+        for bundle in 0..container.bundles.len() as u32 {
+            self.registry.load_from_container(
+                &container,
+                bundle,
+                shared.as_ref(),
+                Some(&mut report.failures),
+            )?;
+        }
+
+        // Assets whose hooks fail are removed once all hooks ran.
+        let mut failed = Vec::new();
+        self.registry
+            .run_hooks(&container, None, Some(&mut failed))?;
+        let handles: HashSet<u32> = failed
+            .iter()
+            .filter_map(|failure| {
+                let entry = &container.entries[failure.entry];
+                let registration = self.registry.types.get(&entry.type_tag)?;
+                let index = self
+                    .registry
+                    .index(&registration.r#type, &entry.metadata.name)?;
+                Some(self.registry.slots[index].handle)
+            })
+            .collect();
+        if !handles.is_empty() {
+            self.registry
+                .remove_slots(|slot| handles.contains(&slot.handle), &HashSet::new());
+        }
+        report.failures.extend(failed);
+
+        Ok((self.registry, report))
+    }
+
+    /// Loads all bundles of a compiled container starting at the current position of
+    /// `reader`, reading only the entries that are deserialized.
+    ///
+    /// The header and entry table are read first. Every entry is then read by seeking to it,
+    /// so in [`LoadMode::Lazy`] only the assets that are accessed are ever read, which allows
+    /// loading single assets out of large pack files or containers nested in other files.
+    /// The registry keeps `reader` to read them from until all of them are deserialized.
+    /// `Bytes` payloads are copied out of the reader.
+    pub fn load_from<R: Read + Seek + Send + 'static>(
+        mut self,
+        mut reader: R,
+    ) -> Result<Registry, Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let start = reader.stream_position()?;
+        let container = Container::read_table(&mut reader, start)?;
+        let source = Source::Reader {
+            reader: Arc::new(Mutex::new(reader)),
+            start,
+        };
+        for bundle in 0..container.bundles.len() as u32 {
+            self.registry
+                .load_from_container(&container, bundle, Some(&source), None)?;
+        }
+        self.registry.run_hooks(&container, None, None)?;
+
+        Ok(self.registry)
+    }
+
+    /// Memory-maps a compiled container and loads all of its bundles into the registry.
+    ///
+    /// `Bytes` fields of the assets borrow their payloads from the mapping instead of copying
+    /// them, and in [`LoadMode::Lazy`] the assets are deserialized from it on first access.
+    /// The file must not be modified while the registry or any of its `Bytes` are alive.
+    #[cfg(feature = "mmap")]
+    pub fn open_mmap(mut self, path: impl AsRef<std::path::Path>) -> Result<Registry, Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the mapping is read-only and the caller guarantees the file is not modified.
+        let mmap = Arc::new(unsafe { memmap2::Mmap::map(&file)? });
+        let container = Container::parse(&mmap)?;
+        let source = Source::shared(mmap.clone());
+        for bundle in 0..container.bundles.len() as u32 {
+            self.registry
+                .load_from_container(&container, bundle, Some(&source), None)?;
         }
+        self.registry.run_hooks(&container, None, None)?;
 
         Ok(self.registry)
     }
 }
 
-pub type Deserializer = Box<dyn Fn(&[u8]) -> Result<Box<dyn Asset>, Error> + Send + Sync>;
+/// Builder for the migrations of an asset type, created by
+/// `RegistryBuilder::reg_type_with_migrations`.
+pub struct Migrations<T> {
+    builder: RegistryBuilder,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Asset + 'static> Migrations<T> {
+    /// Adds a migration for entries compiled with schema version `version`.
+    ///
+    /// Such entries are decoded as `Old` and converted with `migrate`. `Old` must have the
+    /// field layout the type had in that version. Bincode entries are decoded with bincode's
+    /// standard configuration, the one used by `asset_def!`, and entries in other formats
+    /// with serde.
+    pub fn migrate<Old, F>(mut self, version: u32, migrate: F) -> Self
+    where
+        Old: bincode::Decode<()> + serde::de::DeserializeOwned,
+        F: Fn(Old) -> T + Send + Sync + 'static,
+    {
+        let r#type = Type(std::any::TypeId::of::<T>());
+        if let Some(registration) = self.builder.registry.types.get_mut(T::static_type_tag())
+            && registration.r#type == r#type
+        {
+            let type_name = registration.type_name;
+            registration.migrations.insert(
+                version,
+                Arc::new(move |bytes, format| {
+                    let old = match format {
+                        Format::Bincode => {
+                            bincode::decode_from_slice(bytes, bincode::config::standard())
+                                .map(|(old, _)| old)
+                                .map_err(|e| {
+                                    Error::Deserialization(format!(
+                                        "Failed to deserialize version {} of {}: {}",
+                                        version, type_name, e
+                                    ))
+                                })?
+                        }
+                        format => format.deserialize(bytes)?,
+                    };
+                    Ok(Box::new(migrate(old)) as Box<dyn Asset>)
+                }),
+            );
+        }
+        self
+    }
+
+    /// Returns the registry builder once all migrations are added.
+    pub fn done(self) -> RegistryBuilder {
+        self.builder
+    }
+}
+
+pub type Deserializer = Arc<dyn Fn(&[u8], Format) -> Result<Box<dyn Asset>, Error> + Send + Sync>;
+
+/// Hook added by [`RegistryBuilder::on_load`].
+type Hook = Arc<dyn Fn(&dyn Asset, &Registry) -> Result<(), Error> + Send + Sync>;
+
+/// A registered asset type, keyed by its type tag.
+#[derive(Clone)]
+struct Registration {
+    r#type: Type,
+    type_name: &'static str,
+    /// Schema version `deserializer` decodes, see [`Asset::schema_version`].
+    version: u32,
+    deserializer: Deserializer,
+    /// Deserializers converting entries of older schema versions, by version.
+    migrations: HashMap<u32, Deserializer>,
+    #[cfg(feature = "ron")]
+    from_value: crate::ron_loader::ValueDeserializer,
+}
+
+impl Registration {
+    /// Returns the deserializer for entries of the given schema version.
+    ///
+    /// Fails with [`Error::MissingMigration`] if the version is neither the current one nor
+    /// has a migration.
+    fn deserializer(&self, version: u32) -> Result<&Deserializer, Error> {
+        if version == self.version {
+            return Ok(&self.deserializer);
+        }
+        self.migrations
+            .get(&version)
+            .ok_or_else(|| Error::MissingMigration {
+                type_name: self.type_name.to_string(),
+                found: version,
+                expected: self.version,
+            })
+    }
+}
+
+/// A bundle whose assets are currently in the registry.
+struct LoadedBundle {
+    id: u32,
+    metadata: Metadata,
+    /// Assets of unregistered types kept under [`UnknownTypePolicy::Raw`], with their
+    /// metadata.
+    raw: Vec<(Metadata, RawAsset)>,
+    /// Assets of unregistered types left out under [`UnknownTypePolicy::Skip`].
+    skipped: Vec<Metadata>,
+}
+
+/// Result of unloading a bundle from a [`Registry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnloadReport {
+    /// Metadata of the unloaded bundle.
+    pub bundle: Metadata,
+    /// Number of assets dropped from the registry.
+    pub assets: usize,
+    /// Number of dropped assets that are still kept alive by an `Arc` from
+    /// [`Registry::get_arc`].
+    pub outstanding: usize,
+}
+
+/// Result of loading a container with `RegistryBuilder::load_with_report`.
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    /// Entries that were skipped.
+    pub failures: Vec<LoadFailure>,
+}
+
+impl LoadReport {
+    /// Returns whether every entry was loaded.
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Entry of a container that could not be loaded.
+#[derive(Debug)]
+pub struct LoadFailure {
+    /// Index of the entry in the entry table of the container.
+    pub entry: usize,
+    /// Type name the asset was compiled with.
+    pub type_name: String,
+    pub name: String,
+    pub error: Error,
+}
+
+impl LoadFailure {
+    /// Records the failure of the entry at `index` of a container.
+    fn new(index: usize, entry: &Entry, error: Error) -> Self {
+        LoadFailure {
+            entry: index,
+            type_name: entry.metadata.type_name.clone(),
+            name: entry.metadata.name.clone(),
+            error,
+        }
+    }
+}
+
+/// Change to an asset made by [`Registry::reload`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetEvent {
+    pub kind: AssetEventKind,
+    pub r#type: Type,
+    pub type_name: &'static str,
+    pub name: String,
+}
+
+/// Kind of change reported by an [`AssetEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetEventKind {
+    /// The asset was not loaded before.
+    Added,
+    /// The serialized bytes of the asset changed.
+    Changed,
+    /// The asset is no longer part of the reloaded bundles.
+    Removed,
+}
+
+/// Serialized bytes of an asset that has not been deserialized yet.
+struct Pending {
+    source: Source,
+    range: Range<usize>,
+    codec: Codec,
+    raw_len: usize,
+    /// Ranges of the `Bytes` payloads of the asset in the container.
+    blobs: Vec<Range<usize>>,
+    /// Expected checksum of the entry, or `None` if it is not verified.
+    checksum: Option<u64>,
+    /// Whether the asset is validated after it is deserialized.
+    validate: bool,
+    format: Format,
+    deserializer: Deserializer,
+}
+
+impl Pending {
+    /// Verifies and deserializes the asset with the given name.
+    fn deserialize(&self, name: &str) -> Result<Box<dyn Asset>, Error> {
+        let (bytes, blobs) = self.source.read(self.range.clone(), &self.blobs)?;
+        if let Some(checksum) = self.checksum {
+            let mut parts = vec![bytes.as_ref()];
+            parts.extend(blobs.iter().map(|blob| blob.as_ref()));
+            container::verify(name, checksum, &parts)?;
+        }
+        match self.codec {
+            Codec::None => blob::provide(blobs, || (self.deserializer)(&bytes, self.format)),
+            codec => {
+                let bytes = codec.decompress(&bytes, self.raw_len)?;
+                blob::provide(blobs, || (self.deserializer)(&bytes, self.format))
+            }
+        }
+    }
+}
+
+/// Bundle of a container whose entries are being loaded, before it is added.
+pub(crate) struct BundleLoad {
+    bundle: LoadedBundle,
+    slots: Vec<Slot>,
+}
+
+impl BundleLoad {
+    /// Starts loading the bundle at index `bundle` of a parsed container.
+    pub(crate) fn new(container: &Container, bundle: u32) -> Self {
+        BundleLoad {
+            bundle: LoadedBundle {
+                id: 0,
+                metadata: container.bundles[bundle as usize].clone(),
+                raw: Vec::new(),
+                skipped: Vec::new(),
+            },
+            slots: Vec::new(),
+        }
+    }
+
+    /// Adds the entry at `index` of a parsed container, recording it in `failures` if it
+    /// failed to load and `failures` is given.
+    fn add(
+        &mut self,
+        container: &Container,
+        index: usize,
+        loaded: Result<Loaded, Error>,
+        failures: Option<&mut Vec<LoadFailure>>,
+    ) -> Result<(), Error> {
+        let entry = &container.entries[index];
+        match (loaded, failures) {
+            (Ok(Loaded::Slot(slot)), _) => self.slots.push(slot),
+            (Ok(Loaded::Raw(raw)), _) => self.bundle.raw.push((entry.metadata.clone(), raw)),
+            (Ok(Loaded::Skipped), _) => self.bundle.skipped.push(entry.metadata.clone()),
+            (Err(error), Some(failures)) => failures.push(LoadFailure::new(index, entry, error)),
+            (Err(error), None) => return Err(error),
+        }
+        Ok(())
+    }
+}
+
+/// Entry of a container loaded by [`Registry::load_entry_at`].
+enum Loaded {
+    Slot(Slot),
+    Raw(RawAsset),
+    /// The entry has an unregistered type and is skipped.
+    Skipped,
+}
+
+/// An asset in the registry, deserialized while loading or on first access.
+struct Slot {
+    r#type: Type,
+    type_name: &'static str,
+    metadata: Metadata,
+    /// Id of the bundle the asset was loaded from.
+    owner: u32,
+    /// Index into the handle table, assigned by [`Registry::insert`].
+    handle: u32,
+    /// Type and name of the assets this asset refers to.
+    dependencies: Vec<(Type, String)>,
+    asset: OnceLock<Arc<dyn Asset>>,
+    pending: Option<Pending>,
+}
+
+/// Entry of the handle table, pointing at a slot while its asset is loaded.
+struct HandleEntry {
+    generation: u32,
+    slot: Option<usize>,
+}
+
+impl Slot {
+    /// Returns the asset, deserializing it on first access.
+    fn asset(&self) -> Result<&dyn Asset, Error> {
+        self.shared().map(|asset| asset.as_ref())
+    }
+
+    /// Returns the shared asset, deserializing it on first access.
+    fn shared(&self) -> Result<&Arc<dyn Asset>, Error> {
+        if let Some(asset) = self.asset.get() {
+            return Ok(asset);
+        }
+        let pending = self.pending.as_ref().ok_or_else(|| {
+            Error::Deserialization(format!("Asset '{}' has no data", self.metadata.name))
+        })?;
+        let asset = pending.deserialize(&self.metadata.name)?;
+        if pending.validate {
+            validation::validate(asset.as_ref(), ValidationStage::Load)?;
+        }
+        Ok(self.asset.get_or_init(|| Arc::from(asset)))
+    }
+
+    /// Returns whether the asset is kept alive outside of the registry.
+    fn is_shared(&self) -> bool {
+        self.asset
+            .get()
+            .is_some_and(|asset| Arc::strong_count(asset) > 1)
+    }
+}
 
 pub struct Registry {
-    deserializers: HashMap<String, Deserializer>,
-    assets: Vec<Box<dyn Asset>>,
+    types: HashMap<String, Registration>,
+    slots: Vec<Slot>,
+    /// Index into `slots` by asset type and name.
+    names: HashMap<Type, HashMap<String, usize>>,
+    /// Indices into `slots` grouped by asset type, in load order.
+    by_type: HashMap<Type, Vec<usize>>,
+    bundles: Vec<LoadedBundle>,
+    next_bundle: u32,
+    handles: Vec<HandleEntry>,
+    /// Indices into `handles` that are not used by any slot.
+    free_handles: Vec<u32>,
+    hooks: HashMap<Type, Vec<Hook>>,
+    duplicates: DuplicatePolicy,
+    unknown_types: UnknownTypePolicy,
+    mode: LoadMode,
+    verify: bool,
+    validate: bool,
+    #[cfg(feature = "hot-reload")]
+    pub(crate) watches: crate::watch::Watches,
 }
 
 impl Registry {
     fn new() -> Self {
         Registry {
-            deserializers: HashMap::new(),
-            assets: Vec::new(),
+            types: HashMap::new(),
+            slots: Vec::new(),
+            names: HashMap::new(),
+            by_type: HashMap::new(),
+            bundles: Vec::new(),
+            next_bundle: 0,
+            handles: Vec::new(),
+            free_handles: Vec::new(),
+            hooks: HashMap::new(),
+            duplicates: DuplicatePolicy::default(),
+            unknown_types: UnknownTypePolicy::default(),
+            mode: LoadMode::default(),
+            verify: true,
+            validate: true,
+            #[cfg(feature = "hot-reload")]
+            watches: Default::default(),
+        }
+    }
+
+    /// Adds an asset and records it in the lookup indexes, applying the duplicate policy.
+    ///
+    /// The asset takes over the `reuse` handle if given, otherwise a new one is assigned.
+    fn insert(&mut self, mut slot: Slot, reuse: Option<u32>) -> Result<(), Error> {
+        if let Some(existing) = self.index(&slot.r#type, &slot.metadata.name) {
+            return match self.duplicates {
+                DuplicatePolicy::Error => Err(Error::DuplicateAsset {
+                    type_name: slot.type_name.to_string(),
+                    name: slot.metadata.name,
+                }),
+                DuplicatePolicy::FirstWins => {
+                    if let Some(handle) = reuse {
+                        self.free_handle(handle);
+                    }
+                    Ok(())
+                }
+                DuplicatePolicy::LastWins => {
+                    self.free_handle(self.slots[existing].handle);
+                    slot.handle = self.claim_handle(reuse, existing);
+                    self.slots[existing] = slot;
+                    Ok(())
+                }
+            };
+        }
+
+        let index = self.slots.len();
+        self.names
+            .entry(slot.r#type.clone())
+            .or_default()
+            .insert(slot.metadata.name.clone(), index);
+        self.by_type
+            .entry(slot.r#type.clone())
+            .or_default()
+            .push(index);
+        slot.handle = self.claim_handle(reuse, index);
+        self.slots.push(slot);
+        Ok(())
+    }
+
+    /// Points the `reuse` handle, or a new one, at a slot.
+    fn claim_handle(&mut self, reuse: Option<u32>, slot: usize) -> u32 {
+        if let Some(handle) = reuse.or_else(|| self.free_handles.pop()) {
+            self.handles[handle as usize].slot = Some(slot);
+            return handle;
+        }
+        self.handles.push(HandleEntry {
+            generation: 0,
+            slot: Some(slot),
+        });
+        (self.handles.len() - 1) as u32
+    }
+
+    /// Invalidates a handle so that it can be reused with the next generation.
+    fn free_handle(&mut self, handle: u32) {
+        let entry = &mut self.handles[handle as usize];
+        entry.generation = entry.generation.wrapping_add(1);
+        entry.slot = None;
+        self.free_handles.push(handle);
+    }
+
+    /// Removes the slots matching `remove` and rebuilds the lookup indexes.
+    ///
+    /// Handles of the removed assets are invalidated, except for the `reused` ones, which are
+    /// taken over by assets inserted afterwards. Returns the number of removed assets.
+    fn remove_slots(&mut self, remove: impl Fn(&Slot) -> bool, reused: &HashSet<u32>) -> usize {
+        let mut removed = Vec::new();
+        self.slots.retain(|slot| {
+            let keep = !remove(slot);
+            if !keep {
+                removed.push(slot.handle);
+            }
+            keep
+        });
+        for &handle in &removed {
+            if reused.contains(&handle) {
+                self.handles[handle as usize].slot = None;
+            } else {
+                self.free_handle(handle);
+            }
+        }
+        self.reindex();
+        removed.len()
+    }
+
+    /// Returns the first slot that has the same type and name as an existing asset or as an
+    /// earlier slot.
+    fn find_duplicate<'a>(&self, slots: impl Iterator<Item = &'a Slot>) -> Option<&'a Slot> {
+        let mut seen = HashSet::new();
+        slots.into_iter().find(|slot| {
+            let exists = self
+                .names
+                .get(&slot.r#type)
+                .is_some_and(|names| names.contains_key(&slot.metadata.name));
+            exists || !seen.insert((&slot.r#type, slot.metadata.name.as_str()))
+        })
+    }
+
+    /// Adds the assets of a bundle.
+    ///
+    /// With [`DuplicatePolicy::Error`] nothing is added if any asset is a duplicate.
+    fn insert_bundle(&mut self, bundle: LoadedBundle, slots: Vec<Slot>) -> Result<(), Error> {
+        if self.has_bundle(&bundle.metadata.name) {
+            return Err(Error::BundleLoaded(bundle.metadata.name));
+        }
+        if self.duplicates == DuplicatePolicy::Error
+            && let Some(slot) = self.find_duplicate(slots.iter())
+        {
+            return Err(Error::DuplicateAsset {
+                type_name: slot.type_name.to_string(),
+                name: slot.metadata.name.clone(),
+            });
+        }
+
+        let id = self.next_bundle;
+        self.next_bundle += 1;
+        self.bundles.push(LoadedBundle { id, ..bundle });
+        for mut slot in slots {
+            slot.owner = id;
+            self.insert(slot, None)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the assets of a bundle of a parsed container.
+    ///
+    /// When `source` is given it must hold the container. Assets are then read from it,
+    /// borrowing their `Bytes` payloads if it is shared, and in [`LoadMode::Lazy`] keep a
+    /// reference to it to be deserialized from later. Otherwise the payloads are copied out
+    /// of the parsed bytes.
+    ///
+    /// When `failures` is given, entries that fail to load or validate are recorded there and
+    /// skipped instead of failing the whole bundle.
+    fn load_from_container(
+        &mut self,
+        container: &Container,
+        bundle: u32,
+        source: Option<&Source>,
+        mut failures: Option<&mut Vec<LoadFailure>>,
+    ) -> Result<(), Error> {
+        let mut load = BundleLoad::new(container, bundle);
+        let indices: Vec<usize> = (0..container.entries.len())
+            .filter(|&index| container.entries[index].bundle == bundle)
+            .collect();
+        let record = failures.is_some();
+        // Entries are decoded in parallel, but added in their order in the container, so
+        // the registry and the first error reported are the same as when loading in order.
+        #[cfg(feature = "parallel")]
+        let loaded: Vec<_> = indices
+            .par_iter()
+            .map(|&index| self.load_entry_at(container, index, source, record))
+            .collect();
+        #[cfg(not(feature = "parallel"))]
+        let loaded = indices
+            .iter()
+            .map(|&index| self.load_entry_at(container, index, source, record));
+        // Duplicates are recorded as failures here, so that adding the bundle does not fail.
+        let mut seen = HashSet::new();
+        for (index, loaded) in indices.iter().zip(loaded) {
+            let loaded = match loaded {
+                Ok(Loaded::Slot(slot)) if record => self
+                    .check_duplicate(&slot, &mut seen)
+                    .map(|()| Loaded::Slot(slot)),
+                loaded => loaded,
+            };
+            load.add(container, *index, loaded, failures.as_deref_mut())?;
+        }
+        self.finish_bundle(load, !record)
+    }
+
+    /// Fails with [`Error::DuplicateAsset`] under [`DuplicatePolicy::Error`] if the asset of a
+    /// slot is already loaded or in `seen`, adding it to `seen` otherwise.
+    fn check_duplicate(
+        &self,
+        slot: &Slot,
+        seen: &mut HashSet<(Type, String)>,
+    ) -> Result<(), Error> {
+        if self.duplicates != DuplicatePolicy::Error {
+            return Ok(());
+        }
+        let exists = self.index(&slot.r#type, &slot.metadata.name).is_some();
+        if exists || !seen.insert((slot.r#type.clone(), slot.metadata.name.clone())) {
+            return Err(Error::DuplicateAsset {
+                type_name: slot.type_name.to_string(),
+                name: slot.metadata.name.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Loads the entry at `index` of a parsed container into a bundle being loaded, see
+    /// [`Registry::load_from_container`].
+    #[cfg(feature = "async")]
+    pub(crate) fn load_into(
+        &self,
+        load: &mut BundleLoad,
+        container: &Container,
+        index: usize,
+        source: Option<&Source>,
+        failures: Option<&mut Vec<LoadFailure>>,
+    ) -> Result<(), Error> {
+        let loaded = self.load_entry_at(container, index, source, failures.is_some());
+        load.add(container, index, loaded, failures)
+    }
+
+    /// Loads the entry at `index` of a parsed container without changing the registry.
+    ///
+    /// With `validate` set the asset is validated here, otherwise invalid assets are
+    /// reported together by [`Registry::finish_bundle`].
+    fn load_entry_at(
+        &self,
+        container: &Container,
+        index: usize,
+        source: Option<&Source>,
+        validate: bool,
+    ) -> Result<Loaded, Error> {
+        let entry = &container.entries[index];
+        let known = self.types.contains_key(&entry.type_tag);
+        match self.unknown_types {
+            UnknownTypePolicy::Skip if !known => Ok(Loaded::Skipped),
+            UnknownTypePolicy::Raw if !known => {
+                RawAsset::from_entry(container, entry, source, self.verify).map(Loaded::Raw)
+            }
+            _ => {
+                let slot = self.load_entry(container, entry, source)?;
+                if validate
+                    && self.validate
+                    && let Some(asset) = slot.asset.get()
+                {
+                    validation::validate(asset.as_ref(), ValidationStage::Load)?;
+                }
+                Ok(Loaded::Slot(slot))
+            }
+        }
+    }
+
+    /// Adds a loaded bundle, first reporting all invalid assets together if `validate` is set.
+    pub(crate) fn finish_bundle(&mut self, load: BundleLoad, validate: bool) -> Result<(), Error> {
+        if validate && self.validate {
+            validation::report(
+                load.slots
+                    .iter()
+                    .filter_map(|slot| slot.asset.get())
+                    .filter_map(|asset| {
+                        validation::validate(asset.as_ref(), ValidationStage::Load).err()
+                    })
+                    .collect(),
+            )?;
+        }
+
+        self.insert_bundle(load.bundle, load.slots)
+    }
+
+    /// Creates the slot for an entry of a parsed container, deserializing it unless it is
+    /// loaded lazily.
+    fn load_entry(
+        &self,
+        container: &Container,
+        entry: &Entry,
+        source: Option<&Source>,
+    ) -> Result<Slot, Error> {
+        let registration = self
+            .types
+            .get(&entry.type_tag)
+            .ok_or_else(|| Error::TypeNotFound(entry.type_tag.clone()))?;
+        let deserializer = registration.deserializer(entry.schema_version)?;
+
+        let slot = Slot {
+            r#type: registration.r#type.clone(),
+            type_name: registration.type_name,
+            metadata: entry.metadata.clone(),
+            owner: 0,
+            handle: 0,
+            dependencies: entry
+                .dependencies
+                .iter()
+                .filter_map(|&dependency| {
+                    let dependency = &container.entries[dependency as usize];
+                    let registration = self.types.get(&dependency.type_tag)?;
+                    Some((
+                        registration.r#type.clone(),
+                        dependency.metadata.name.clone(),
+                    ))
+                })
+                .collect(),
+            asset: OnceLock::new(),
+            pending: None,
+        };
+        match source {
+            Some(source) => {
+                let pending = self.pending(container, entry, source, deserializer)?;
+                if self.mode == LoadMode::Lazy {
+                    Ok(Slot {
+                        pending: Some(pending),
+                        ..slot
+                    })
+                } else {
+                    Ok(Slot {
+                        asset: OnceLock::from(Arc::from(
+                            pending.deserialize(&entry.metadata.name)?,
+                        )),
+                        ..slot
+                    })
+                }
+            }
+            None => {
+                if self.verify {
+                    container.verify_entry(entry)?;
+                }
+                let blobs = container
+                    .blob_ranges(entry)?
+                    .into_iter()
+                    .map(|range| Bytes::from(&container.binary()[range]))
+                    .collect();
+                let bytes = container.entry_data(entry)?;
+                let asset = blob::provide(blobs, || deserializer(&bytes, container.header.format))?;
+                Ok(Slot {
+                    asset: OnceLock::from(Arc::from(asset)),
+                    ..slot
+                })
+            }
+        }
+    }
+
+    /// Prepares an entry to be deserialized from a source.
+    fn pending(
+        &self,
+        container: &Container,
+        entry: &Entry,
+        source: &Source,
+        deserializer: &Deserializer,
+    ) -> Result<Pending, Error> {
+        let (range, blobs) = source.ranges(entry)?;
+        Ok(Pending {
+            source: source.clone(),
+            range,
+            codec: entry.codec,
+            raw_len: entry.raw_len as usize,
+            blobs,
+            checksum: self.verify.then_some(entry.checksum),
+            validate: self.validate,
+            format: container.header.format,
+            deserializer: deserializer.clone(),
+        })
+    }
+
+    /// Deserializes and validates an entry of a container without adding it to the registry.
+    #[cfg(feature = "async")]
+    pub(crate) fn deserialize_entry(
+        &self,
+        container: &Container,
+        entry: &Entry,
+        source: &Source,
+    ) -> Result<Box<dyn Asset>, Error> {
+        let registration = self
+            .types
+            .get(&entry.type_tag)
+            .ok_or_else(|| Error::TypeNotFound(entry.type_tag.clone()))?;
+        let deserializer = registration.deserializer(entry.schema_version)?;
+        let asset = self
+            .pending(container, entry, source, deserializer)?
+            .deserialize(&entry.metadata.name)?;
+        if self.validate {
+            validation::validate(asset.as_ref(), ValidationStage::Load)?;
+        }
+        Ok(asset)
+    }
+
+    /// Runs the load hooks for the assets of a container, or of one of its bundles, in the
+    /// load order of the container.
+    ///
+    /// When `failures` is given, hooks that fail are recorded there instead of stopping.
+    fn run_hooks(
+        &self,
+        container: &Container,
+        bundle: Option<u32>,
+        mut failures: Option<&mut Vec<LoadFailure>>,
+    ) -> Result<(), Error> {
+        if self.hooks.is_empty() {
+            return Ok(());
+        }
+        for &position in &container.order {
+            let entry = &container.entries[position as usize];
+            if bundle.is_some_and(|bundle| bundle != entry.bundle) {
+                continue;
+            }
+            let Some(registration) = self.types.get(&entry.type_tag) else {
+                continue;
+            };
+            let (Some(hooks), Some(index)) = (
+                self.hooks.get(&registration.r#type),
+                self.index(&registration.r#type, &entry.metadata.name),
+            ) else {
+                continue;
+            };
+            let ran = self.slots[index]
+                .asset()
+                .and_then(|asset| hooks.iter().try_for_each(|hook| hook(asset, self)));
+            match (ran, failures.as_deref_mut()) {
+                (Ok(()), _) => {}
+                (Err(error), Some(failures)) => {
+                    failures.push(LoadFailure::new(position as usize, entry, error));
+                }
+                (Err(error), None) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies the binary for assets that are deserialized on first access.
+    fn share(&self, binary: &[u8]) -> Option<Source> {
+        (self.mode == LoadMode::Lazy).then(|| Source::shared(Arc::new(binary.to_vec())))
+    }
+
+    /// Rebuilds the lookup indexes and handle table after assets were removed.
+    fn reindex(&mut self) {
+        self.names.clear();
+        self.by_type.clear();
+        for (index, slot) in self.slots.iter().enumerate() {
+            self.handles[slot.handle as usize].slot = Some(index);
+            self.names
+                .entry(slot.r#type.clone())
+                .or_default()
+                .insert(slot.metadata.name.clone(), index);
+            self.by_type
+                .entry(slot.r#type.clone())
+                .or_default()
+                .push(index);
         }
     }
 
     /// Registers a type in the registry with its deserializer.
-    fn reg_type<T>(&mut self)
+    fn reg_type<T>(&mut self) -> Result<(), Error>
     where
         T: Asset + 'static,
     {
-        let type_name = std::any::type_name::<T>().to_string();
-        self.deserializers.insert(
-            type_name,
-            Box::new(|bytes| T::from_bytes(bytes).map(|asset| Box::new(asset) as Box<dyn Asset>)),
+        let type_tag = T::static_type_tag();
+        let r#type = Type(std::any::TypeId::of::<T>());
+        if let Some(existing) = self.types.get(type_tag) {
+            if existing.r#type == r#type {
+                return Ok(());
+            }
+            return Err(Error::TypeTagCollision {
+                type_tag: type_tag.to_string(),
+                first: existing.type_name.to_string(),
+                second: std::any::type_name::<T>().to_string(),
+            });
+        }
+
+        self.types.insert(
+            type_tag.to_string(),
+            Registration {
+                r#type,
+                type_name: std::any::type_name::<T>(),
+                version: T::static_schema_version(),
+                deserializer: Arc::new(|bytes, format| {
+                    T::from_bytes_as(bytes, format).map(|asset| Box::new(asset) as Box<dyn Asset>)
+                }),
+                migrations: HashMap::new(),
+                #[cfg(feature = "ron")]
+                from_value: Arc::new(|value| {
+                    T::from_deserializer(value).map(|asset| Box::new(asset) as Box<dyn Asset>)
+                }),
+            },
         );
+        Ok(())
+    }
+
+    /// Moves all types, bundles and assets of another registry into this one.
+    ///
+    /// Assets with a type and name already present are handled by this registry's duplicate
+    /// policy. With [`DuplicatePolicy::Error`] nothing is merged if any asset is a duplicate.
+    pub fn merge(&mut self, other: Registry) -> Result<(), Error> {
+        self.absorb(other, HashMap::new())
+    }
+
+    /// Merges another registry, letting its assets take over the handles in `reuse` by type
+    /// and name.
+    fn absorb(
+        &mut self,
+        other: Registry,
+        mut reuse: HashMap<(Type, String), u32>,
+    ) -> Result<(), Error> {
+        for (type_tag, registration) in &other.types {
+            if let Some(existing) = self.types.get(type_tag)
+                && existing.r#type != registration.r#type
+            {
+                return Err(Error::TypeTagCollision {
+                    type_tag: type_tag.clone(),
+                    first: existing.type_name.to_string(),
+                    second: registration.type_name.to_string(),
+                });
+            }
+        }
+        if let Some(bundle) = other
+            .bundles
+            .iter()
+            .find(|bundle| self.has_bundle(&bundle.metadata.name))
+        {
+            return Err(Error::BundleLoaded(bundle.metadata.name.clone()));
+        }
+        if self.duplicates == DuplicatePolicy::Error
+            && let Some(slot) = self.find_duplicate(other.slots.iter())
+        {
+            return Err(Error::DuplicateAsset {
+                type_name: slot.type_name.to_string(),
+                name: slot.metadata.name.clone(),
+            });
+        }
+
+        for (type_tag, registration) in other.types {
+            self.types.entry(type_tag).or_insert(registration);
+        }
+        let offset = self.next_bundle;
+        self.next_bundle += other.next_bundle;
+        self.bundles
+            .extend(other.bundles.into_iter().map(|bundle| LoadedBundle {
+                id: bundle.id + offset,
+                ..bundle
+            }));
+        for mut slot in other.slots {
+            slot.owner += offset;
+            let handle = reuse.remove(&(slot.r#type.clone(), slot.metadata.name.clone()));
+            self.insert(slot, handle)?;
+        }
+        for (_, handle) in reuse {
+            self.free_handle(handle);
+        }
+        Ok(())
+    }
+
+    /// Loads a single bundle of a compiled container into the registry.
+    ///
+    /// Fails with [`Error::BundleLoaded`] if a bundle with the same name is already loaded.
+    /// The bundle is loaded and its load hooks are run before it is added, so on error the
+    /// registry is left unchanged. The hooks see a registry holding only the assets of the
+    /// bundle.
+    pub fn load_bundle(&mut self, binary: &[u8], name: &str) -> Result<(), Error> {
+        let container = Container::parse(binary)?;
+        let bundle = container
+            .bundle_index(name)
+            .ok_or_else(|| Error::BundleNotFound(name.to_string()))?;
+        if self.has_bundle(name) {
+            return Err(Error::BundleLoaded(name.to_string()));
+        }
+        let mut next = self.staging();
+        let shared = next.share(binary);
+        next.load_from_container(&container, bundle, shared.as_ref(), None)?;
+        self.commit_bundle(next, &container, bundle)
+    }
+
+    /// Returns an empty registry with the types, hooks and settings of this one, for assets
+    /// to be loaded into before they are added to this one.
+    pub(crate) fn staging(&self) -> Registry {
+        Registry {
+            types: self.types.clone(),
+            duplicates: self.duplicates,
+            unknown_types: self.unknown_types,
+            mode: self.mode,
+            verify: self.verify,
+            validate: self.validate,
+            hooks: self.hooks.clone(),
+            ..Registry::new()
+        }
+    }
+
+    /// Runs the load hooks for a bundle loaded into a registry from [`Registry::staging`]
+    /// and then adds its assets. Nothing is added if a hook fails.
+    pub(crate) fn commit_bundle(
+        &mut self,
+        next: Registry,
+        container: &Container,
+        bundle: u32,
+    ) -> Result<(), Error> {
+        next.run_hooks(container, Some(bundle), None)?;
+        self.absorb(next, HashMap::new())
+    }
+
+    /// Replaces the bundles of a compiled container with their new contents.
+    ///
+    /// Bundles of the container that are not loaded yet are added. The container is loaded
+    /// completely and its load hooks are run before anything is replaced, so on error the
+    /// registry is left unchanged. The hooks see a registry holding only the assets of the
+    /// container. Returns the assets that were added, changed or removed, compared by their
+    /// hash.
+    pub fn reload(&mut self, binary: &[u8]) -> Result<Vec<AssetEvent>, Error> {
+        self.reload_bundles(binary, &[])
+            .map(|(_bundles, events)| events)
+    }
+
+    /// Reloads a container, also replacing the `previous` bundles it no longer contains.
+    ///
+    /// Returns the names of the bundles of the container with the changes.
+    pub(crate) fn reload_bundles(
+        &mut self,
+        binary: &[u8],
+        previous: &[String],
+    ) -> Result<(Vec<String>, Vec<AssetEvent>), Error> {
+        let container = Container::parse(binary)?;
+        let mut next = self.staging();
+        let shared = next.share(binary);
+        for bundle in 0..container.bundles.len() as u32 {
+            next.load_from_container(&container, bundle, shared.as_ref(), None)?;
+        }
+        // References are compiled into the same container, so they resolve within `next`.
+        next.run_hooks(&container, None, None)?;
+
+        let names: Vec<String> = container
+            .bundles
+            .iter()
+            .map(|bundle| bundle.name.clone())
+            .collect();
+        let replaced: HashSet<u32> = self
+            .bundles
+            .iter()
+            .filter(|bundle| {
+                names.contains(&bundle.metadata.name) || previous.contains(&bundle.metadata.name)
+            })
+            .map(|bundle| bundle.id)
+            .collect();
+        let kept = |slot: &Slot| !replaced.contains(&slot.owner);
+        if self.duplicates == DuplicatePolicy::Error
+            && let Some(slot) = next.slots.iter().find(|slot| {
+                self.index(&slot.r#type, &slot.metadata.name)
+                    .is_some_and(|index| kept(&self.slots[index]))
+            })
+        {
+            return Err(Error::DuplicateAsset {
+                type_name: slot.type_name.to_string(),
+                name: slot.metadata.name.clone(),
+            });
+        }
+
+        let mut events = Vec::new();
+        for slot in &next.slots {
+            let kind = match self.index(&slot.r#type, &slot.metadata.name) {
+                // An asset of a kept bundle is only replaced under `LastWins`.
+                Some(index)
+                    if kept(&self.slots[index])
+                        && self.duplicates == DuplicatePolicy::FirstWins =>
+                {
+                    continue;
+                }
+                Some(index) => {
+                    if self.slots[index].metadata.hash == slot.metadata.hash {
+                        continue;
+                    }
+                    AssetEventKind::Changed
+                }
+                None => AssetEventKind::Added,
+            };
+            events.push(AssetEvent {
+                kind,
+                r#type: slot.r#type.clone(),
+                type_name: slot.type_name,
+                name: slot.metadata.name.clone(),
+            });
+        }
+        for slot in self.slots.iter().filter(|slot| !kept(slot)) {
+            if next.index(&slot.r#type, &slot.metadata.name).is_none() {
+                events.push(AssetEvent {
+                    kind: AssetEventKind::Removed,
+                    r#type: slot.r#type.clone(),
+                    type_name: slot.type_name,
+                    name: slot.metadata.name.clone(),
+                });
+            }
+        }
+
+        // Assets that are still present keep their handles.
+        let reuse: HashMap<(Type, String), u32> = self
+            .slots
+            .iter()
+            .filter(|slot| !kept(slot) && next.index(&slot.r#type, &slot.metadata.name).is_some())
+            .map(|slot| {
+                (
+                    (slot.r#type.clone(), slot.metadata.name.clone()),
+                    slot.handle,
+                )
+            })
+            .collect();
+        self.remove_slots(|slot| !kept(slot), &reuse.values().copied().collect());
+        self.bundles.retain(|bundle| !replaced.contains(&bundle.id));
+        self.absorb(next, reuse)?;
+        Ok((names, events))
+    }
+
+    /// Returns the RON deserializer of the type with the given type id or struct name.
+    ///
+    /// Fails with [`Error::AmbiguousType`] if the struct names of several types match.
+    #[cfg(feature = "ron")]
+    pub(crate) fn value_deserializer(
+        &self,
+        name: &str,
+    ) -> Result<&crate::ron_loader::ValueDeserializer, Error> {
+        if let Some(registration) = self.types.get(name) {
+            return Ok(&registration.from_value);
+        }
+        let mut matches: Vec<&Registration> = self
+            .types
+            .values()
+            .filter(|registration| registration.type_name.rsplit("::").next() == Some(name))
+            .collect();
+        matches.sort_by_key(|registration| registration.type_name);
+        match matches.as_slice() {
+            [] => Err(Error::TypeNotFound(name.to_string())),
+            [registration] => Ok(&registration.from_value),
+            [first, second, ..] => Err(Error::AmbiguousType {
+                name: name.to_string(),
+                first: first.type_name.to_string(),
+                second: second.type_name.to_string(),
+            }),
+        }
+    }
+
+    /// Returns the duplicate policy of the registry.
+    #[cfg(feature = "ron")]
+    pub(crate) fn duplicate_policy(&self) -> DuplicatePolicy {
+        self.duplicates
+    }
+
+    /// Returns a builder that loads into this registry.
+    #[cfg(feature = "ron")]
+    pub(crate) fn into_builder(self) -> RegistryBuilder {
+        RegistryBuilder {
+            registry: self,
+            error: None,
+        }
+    }
+
+    /// Returns the index of the slot with the given type and name.
+    fn index(&self, r#type: &Type, name: &str) -> Option<usize> {
+        self.names.get(r#type)?.get(name).copied()
+    }
+
+    /// Drops all assets that were loaded from the bundle with the given name.
+    ///
+    /// Assets of the bundle that replaced assets of other bundles under
+    /// [`DuplicatePolicy::LastWins`] are dropped as well.
+    pub fn unload_bundle(&mut self, name: &str) -> Result<UnloadReport, Error> {
+        let position = self
+            .bundles
+            .iter()
+            .position(|bundle| bundle.metadata.name == name)
+            .ok_or_else(|| Error::BundleNotFound(name.to_string()))?;
+        let bundle = self.bundles.remove(position);
+
+        let outstanding = self
+            .slots
+            .iter()
+            .filter(|slot| slot.owner == bundle.id && slot.is_shared())
+            .count();
+        let assets = self.remove_slots(|slot| slot.owner == bundle.id, &HashSet::new());
+
+        Ok(UnloadReport {
+            bundle: bundle.metadata,
+            assets,
+            outstanding,
+        })
+    }
+
+    /// Returns whether a bundle with the given name is loaded.
+    pub fn has_bundle(&self, name: &str) -> bool {
+        self.bundles
+            .iter()
+            .any(|bundle| bundle.metadata.name == name)
+    }
+
+    /// Returns an iterator over the metadata of all loaded bundles, in load order.
+    pub fn bundles(&self) -> impl Iterator<Item = &Metadata> {
+        self.bundles.iter().map(|bundle| &bundle.metadata)
     }
 
     /// Creates a new RegistryBuilder to build a registry.
@@ -86,37 +1409,210 @@ impl Registry {
 
     /// Returns a reference to the assets in the registry.
     pub fn amount(&self) -> usize {
-        self.assets.len()
+        self.slots.len()
     }
 
     /// Returns a reference to the assets in the registry.
+    ///
+    /// In [`LoadMode::Lazy`] the asset is deserialized on first access; if that fails, `None`
+    /// is returned. Use [`Registry::try_get_asset`] to get the error instead.
     pub fn get_asset<T: Asset + 'static>(&self, name: &str) -> Option<&T> {
-        let type_id = std::any::TypeId::of::<T>();
-        self.assets.iter().find_map(|asset| {
-            if asset.get_type().0 == type_id && asset.name() == name {
-                asset.as_any().downcast_ref::<T>()
-            } else {
-                None
-            }
-        })
+        self.try_get_asset(name).ok().flatten()
+    }
+
+    /// Returns a reference to the asset, or the error if deserializing it on first access
+    /// failed.
+    pub fn try_get_asset<T: Asset + 'static>(&self, name: &str) -> Result<Option<&T>, Error> {
+        let r#type = Type(std::any::TypeId::of::<T>());
+        let Some(index) = self.names.get(&r#type).and_then(|names| names.get(name)) else {
+            return Ok(None);
+        };
+        Ok(self.slots[*index].asset()?.as_any().downcast_ref::<T>())
+    }
+
+    /// Returns a shared reference to the asset with the given name.
+    ///
+    /// The asset stays alive as long as the `Arc` does, even after it is unloaded or replaced
+    /// by a reload. Returns `None` if the asset does not exist or fails to deserialize.
+    pub fn get_arc<T: Asset + 'static>(&self, name: &str) -> Option<Arc<T>> {
+        self.try_get_arc(name).ok().flatten()
+    }
+
+    /// Returns a shared reference to the asset, or the error if deserializing it on first
+    /// access failed.
+    pub fn try_get_arc<T: Asset + 'static>(&self, name: &str) -> Result<Option<Arc<T>>, Error> {
+        let Some(index) = self.index(&Type(std::any::TypeId::of::<T>()), name) else {
+            return Ok(None);
+        };
+        let asset: Arc<dyn Any + Send + Sync> = self.slots[index].shared()?.clone();
+        Ok(asset.downcast::<T>().ok())
+    }
+
+    /// Returns a handle to the asset with the given name, without deserializing it.
+    pub fn handle<T: Asset + 'static>(&self, name: &str) -> Option<Handle<T>> {
+        let index = self.index(&Type(std::any::TypeId::of::<T>()), name)?;
+        let handle = self.slots[index].handle;
+        Some(Handle::new(
+            handle,
+            self.handles[handle as usize].generation,
+        ))
+    }
+
+    /// Returns the asset a handle refers to, or `None` if the handle is stale.
+    ///
+    /// In [`LoadMode::Lazy`] the asset is deserialized on first access; if that fails, `None`
+    /// is returned.
+    pub fn get<T: Asset + 'static>(&self, handle: Handle<T>) -> Option<&T> {
+        let slot = self.handle_slot(handle)?;
+        self.slots[slot].asset().ok()?.as_any().downcast_ref::<T>()
+    }
+
+    /// Returns whether a handle still refers to a loaded asset.
+    pub fn is_valid<T>(&self, handle: Handle<T>) -> bool {
+        self.handle_slot(handle).is_some()
+    }
+
+    /// Returns the index of the slot a handle refers to, if it is not stale.
+    fn handle_slot<T>(&self, handle: Handle<T>) -> Option<usize> {
+        self.handles
+            .get(handle.index as usize)
+            .filter(|entry| entry.generation == handle.generation)?
+            .slot
     }
 
     /// Returns a vector of all assets of a specific type in the registry.
+    ///
+    /// Assets that fail to deserialize on first access are left out.
     pub fn get_assets<T: Asset + 'static>(&self) -> Vec<&T> {
-        self.assets
+        let r#type = Type(std::any::TypeId::of::<T>());
+        self.by_type
+            .get(&r#type)
+            .map(|indices| {
+                indices
+                    .iter()
+                    .filter_map(|&index| self.slots[index].asset().ok())
+                    .filter_map(|asset| asset.as_any().downcast_ref::<T>())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Deserializes all assets that have not been accessed yet and releases their bytes.
+    pub fn load_all(&mut self) -> Result<(), Error> {
+        // Assets are deserialized in parallel first, so that the loop below only releases
+        // their bytes and reports the first failure in order.
+        #[cfg(feature = "parallel")]
+        self.slots.par_iter().for_each(|slot| {
+            let _ = slot.asset();
+        });
+        for slot in &mut self.slots {
+            slot.asset()?;
+            slot.pending = None;
+        }
+        Ok(())
+    }
+
+    /// Returns the metadata of the asset with the given name.
+    ///
+    /// Assets of unregistered types kept under [`UnknownTypePolicy::Raw`] or
+    /// [`UnknownTypePolicy::Skip`] are included. If assets of several types share the name,
+    /// an asset of a registered type is returned first, otherwise the one loaded first.
+    pub fn metadata(&self, name: &str) -> Option<&Metadata> {
+        self.first_slot(name)
+            .map(|slot| &slot.metadata)
+            .or_else(|| self.unregistered().find(|metadata| metadata.name == name))
+    }
+
+    /// Returns the metadata of the loaded assets the asset with the given name refers to.
+    ///
+    /// If assets of several types share the name, the one loaded first is used.
+    pub fn dependencies(&self, name: &str) -> Vec<&Metadata> {
+        let Some(slot) = self.first_slot(name) else {
+            return Vec::new();
+        };
+        slot.dependencies
             .iter()
-            .filter_map(|asset| {
-                if asset.get_type().0 == std::any::TypeId::of::<T>() {
-                    asset.as_any().downcast_ref::<T>()
-                } else {
-                    None
-                }
+            .filter_map(|(r#type, name)| self.index(r#type, name))
+            .map(|index| &self.slots[index].metadata)
+            .collect()
+    }
+
+    /// Returns the metadata of the loaded assets that refer to the asset with the given name.
+    ///
+    /// If assets of several types share the name, the one loaded first is used.
+    pub fn dependents(&self, name: &str) -> Vec<&Metadata> {
+        let Some(target) = self.first_slot(name) else {
+            return Vec::new();
+        };
+        self.slots
+            .iter()
+            .filter(|slot| {
+                slot.dependencies
+                    .iter()
+                    .any(|(r#type, name)| *r#type == target.r#type && *name == target.metadata.name)
             })
+            .map(|slot| &slot.metadata)
             .collect()
     }
 
-    /// Returns a reference to all assets in the registry.
-    pub fn get_all_assets(&self) -> &Vec<Box<dyn Asset>> {
-        &self.assets
+    /// Returns the slot of the asset with the given name loaded first across all types.
+    fn first_slot(&self, name: &str) -> Option<&Slot> {
+        let index = self
+            .names
+            .values()
+            .filter_map(|names| names.get(name))
+            .min()?;
+        Some(&self.slots[*index])
+    }
+
+    /// Returns the assets of unregistered types kept under [`UnknownTypePolicy::Raw`] with
+    /// the name of their bundle, by bundle in load order.
+    pub fn raw_assets(&self) -> impl Iterator<Item = (&str, &RawAsset)> {
+        self.bundles.iter().flat_map(|bundle| {
+            bundle
+                .raw
+                .iter()
+                .map(|(_, asset)| (bundle.metadata.name.as_str(), asset))
+        })
+    }
+
+    /// Returns the metadata of the assets of unregistered types left out under
+    /// [`UnknownTypePolicy::Skip`], by bundle in load order.
+    pub fn skipped(&self) -> impl Iterator<Item = &Metadata> {
+        self.bundles.iter().flat_map(|bundle| &bundle.skipped)
+    }
+
+    /// Returns an iterator over the metadata of all assets in the registry, in load order,
+    /// followed by the assets of unregistered types kept under [`UnknownTypePolicy::Raw`] or
+    /// [`UnknownTypePolicy::Skip`], by bundle in load order.
+    ///
+    /// To list a container without registering any type, load it with
+    /// [`UnknownTypePolicy::Skip`] or read its entries with [`Container::parse`].
+    pub fn iter_metadata(&self) -> impl Iterator<Item = &Metadata> {
+        self.slots
+            .iter()
+            .map(|slot| &slot.metadata)
+            .chain(self.unregistered())
+    }
+
+    /// Returns the metadata of the assets of unregistered types, by bundle in load order.
+    fn unregistered(&self) -> impl Iterator<Item = &Metadata> {
+        self.bundles.iter().flat_map(|bundle| {
+            bundle
+                .raw
+                .iter()
+                .map(|(metadata, _)| metadata)
+                .chain(&bundle.skipped)
+        })
+    }
+
+    /// Returns references to all assets in the registry, in load order.
+    ///
+    /// Assets that fail to deserialize on first access are left out.
+    pub fn get_all_assets(&self) -> Vec<&dyn Asset> {
+        self.slots
+            .iter()
+            .filter_map(|slot| slot.asset().ok())
+            .collect()
     }
 }
@@ -0,0 +1,125 @@
+//! Runtime loader for RON asset files in the format read by `asset_file!`.
+
+use crate::{
+    asset::Asset,
+    bundle::Bundle,
+    compiler::Compiler,
+    error::Error,
+    registry::{Registry, RegistryBuilder},
+};
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+/// Function building an asset of a registered type from a RON value.
+pub(crate) type ValueDeserializer =
+    Arc<dyn Fn(ron::Value) -> Result<Box<dyn Asset>, Error> + Send + Sync>;
+
+/// Asset entry of a RON asset file.
+#[derive(serde::Deserialize)]
+#[serde(rename = "Asset")]
+struct RonAsset {
+    name: String,
+    #[serde(rename = "type")]
+    ty: String,
+    #[serde(default)]
+    bundle: Option<String>,
+    metadata: BTreeMap<ron::Value, ron::Value>,
+}
+
+impl RegistryBuilder {
+    /// Loads the assets of a RON source, in the format read by `asset_file!`.
+    ///
+    /// Assets are built through serde for the registered types, which are matched by their
+    /// type id or struct name. Paths of `!IncludeBytes`, `!IncludeVec` and `!IncludeStr`
+    /// values are relative to the working directory.
+    pub fn load_ron(self, source: &str) -> Result<Registry, Error> {
+        let registry = self.build()?;
+        let binary = registry.compile_ron(source, Path::new(""))?;
+        registry.into_builder().load(&binary)
+    }
+
+    /// Loads the assets of a RON file, see [`RegistryBuilder::load_ron`].
+    ///
+    /// Included paths are relative to the directory of the file.
+    pub fn load_ron_file(self, path: impl AsRef<Path>) -> Result<Registry, Error> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+        let registry = self.build()?;
+        let binary = registry.compile_ron(&source, path.parent().unwrap_or(Path::new("")))?;
+        registry.into_builder().load(&binary)
+    }
+}
+
+impl Registry {
+    /// Builds the assets of a RON source with the registered types and compiles them into a
+    /// container, so that they can be loaded like any other.
+    pub(crate) fn compile_ron(&self, source: &str, base: &Path) -> Result<Vec<u8>, Error> {
+        let assets: Vec<RonAsset> = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_str(source)
+            .map_err(|e| Error::Deserialization(format!("Failed to parse RON: {}", e)))?;
+
+        let mut compiler = Compiler::new().with_duplicate_policy(self.duplicate_policy());
+        for asset in assets {
+            let deserializer = self.value_deserializer(&asset.ty)?;
+
+            let mut fields = ron::Map::new();
+            fields.insert("name", asset.name.clone());
+            for (key, value) in asset.metadata {
+                fields.insert(key, resolve(value, base)?);
+            }
+            let built = deserializer(ron::Value::Map(fields)).map_err(|e| {
+                Error::Deserialization(format!(
+                    "Asset '{}' of type {}: {}",
+                    asset.name, asset.ty, e
+                ))
+            })?;
+            compiler.add_asset_to(asset.bundle.as_deref().unwrap_or(Bundle::DEFAULT), built);
+        }
+
+        compiler.compile()
+    }
+}
+
+/// Replaces the include tags supported by `asset_file!` with the contents of their files.
+fn resolve(value: ron::Value, base: &Path) -> Result<ron::Value, Error> {
+    Ok(match value {
+        ron::Value::String(string) => {
+            if let Some(path) = string
+                .strip_prefix("!IncludeBytes ")
+                .or_else(|| string.strip_prefix("!IncludeVec "))
+            {
+                let bytes = std::fs::read(base.join(path))?;
+                ron::Value::Seq(
+                    bytes
+                        .into_iter()
+                        .map(|byte| ron::Value::Number(ron::Number::U8(byte)))
+                        .collect(),
+                )
+            } else if let Some(path) = string.strip_prefix("!IncludeStr ") {
+                ron::Value::String(std::fs::read_to_string(base.join(path))?)
+            } else if string.starts_with("!Rust ") {
+                return Err(Error::Deserialization(format!(
+                    "Rust expressions are only supported by asset_file!: {}",
+                    string
+                )));
+            } else {
+                ron::Value::String(string)
+            }
+        }
+        ron::Value::Seq(values) => ron::Value::Seq(
+            values
+                .into_iter()
+                .map(|value| resolve(value, base))
+                .collect::<Result<_, _>>()?,
+        ),
+        ron::Value::Map(map) => ron::Value::Map(
+            map.into_iter()
+                .map(|(key, value)| Ok((key, resolve(value, base)?)))
+                .collect::<Result<_, Error>>()?,
+        ),
+        ron::Value::Option(Some(value)) => {
+            ron::Value::Option(Some(Box::new(resolve(*value, base)?)))
+        }
+        value => value,
+    })
+}
@@ -0,0 +1,141 @@
+use crate::{asset::Asset, error::Error};
+use std::ops::RangeBounds;
+
+/// Where an asset is validated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationStage {
+    /// While the asset is compiled by [`Compiler::compile`](crate::prelude::Compiler::compile).
+    Compile,
+    /// After the asset is deserialized by a [`Registry`](crate::prelude::Registry).
+    Load,
+}
+
+/// Context passed to [`Asset::validate`].
+pub struct ValidationContext<'a> {
+    stage: ValidationStage,
+    name: &'a str,
+    type_name: &'a str,
+}
+
+impl<'a> ValidationContext<'a> {
+    pub(crate) fn new(stage: ValidationStage, name: &'a str, type_name: &'a str) -> Self {
+        ValidationContext {
+            stage,
+            name,
+            type_name,
+        }
+    }
+
+    /// Returns whether the asset is being compiled or loaded.
+    pub fn stage(&self) -> ValidationStage {
+        self.stage
+    }
+
+    /// Returns the name of the validated asset.
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Creates an [`Error::InvalidAsset`] for the validated asset.
+    pub fn error(&self, reason: impl Into<String>) -> Error {
+        Error::InvalidAsset {
+            asset: self.name.to_string(),
+            type_name: self.type_name.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Validates an asset, returning its failure.
+pub(crate) fn validate(asset: &dyn Asset, stage: ValidationStage) -> Result<(), Error> {
+    let name = asset.name();
+    asset.validate(&ValidationContext::new(stage, &name, asset.type_name()))
+}
+
+/// Combines the failures of several assets into one [`Error::Validation`].
+pub(crate) fn report(failures: Vec<Error>) -> Result<(), Error> {
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Validation(failures))
+    }
+}
+
+/// Values checked by `#[range(..)]` fields in `asset_def!`.
+///
+/// Containers and tuples are in range if all of their values are.
+pub trait InRange {
+    type Value: PartialOrd;
+
+    /// Returns whether all values are inside `range`.
+    fn in_range(&self, range: &impl RangeBounds<Self::Value>) -> bool;
+}
+
+macro_rules! in_range_scalar {
+    ($($ty:ty),*) => {
+        $(
+            impl InRange for $ty {
+                type Value = $ty;
+
+                fn in_range(&self, range: &impl RangeBounds<Self::Value>) -> bool {
+                    range.contains(self)
+                }
+            }
+        )*
+    };
+}
+
+in_range_scalar!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, char
+);
+
+impl<T: InRange> InRange for Option<T> {
+    type Value = T::Value;
+
+    fn in_range(&self, range: &impl RangeBounds<Self::Value>) -> bool {
+        self.as_ref().is_none_or(|value| value.in_range(range))
+    }
+}
+
+impl<T: InRange> InRange for Box<T> {
+    type Value = T::Value;
+
+    fn in_range(&self, range: &impl RangeBounds<Self::Value>) -> bool {
+        (**self).in_range(range)
+    }
+}
+
+impl<T: InRange> InRange for Vec<T> {
+    type Value = T::Value;
+
+    fn in_range(&self, range: &impl RangeBounds<Self::Value>) -> bool {
+        self.iter().all(|value| value.in_range(range))
+    }
+}
+
+impl<T: InRange, const N: usize> InRange for [T; N] {
+    type Value = T::Value;
+
+    fn in_range(&self, range: &impl RangeBounds<Self::Value>) -> bool {
+        self.iter().all(|value| value.in_range(range))
+    }
+}
+
+macro_rules! in_range_tuple {
+    ($first:ident $(, $rest:ident)*) => {
+        impl<$first: InRange $(, $rest: InRange<Value = $first::Value>)*> InRange for ($first, $($rest,)*) {
+            type Value = $first::Value;
+
+            #[allow(non_snake_case)]
+            fn in_range(&self, range: &impl RangeBounds<Self::Value>) -> bool {
+                let ($first, $($rest,)*) = self;
+                $first.in_range(range) $(&& $rest.in_range(range))*
+            }
+        }
+    };
+}
+
+in_range_tuple!(A);
+in_range_tuple!(A, B);
+in_range_tuple!(A, B, C);
+in_range_tuple!(A, B, C, D);
@@ -0,0 +1,171 @@
+//! Hot reloading of compiled containers and RON asset files that change on disk.
+
+use crate::{
+    error::Error,
+    registry::{AssetEvent, Registry},
+};
+use notify::Watcher;
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{Mutex, mpsc},
+};
+
+/// Files watched by a [`Registry`] and the subscribers to its changes.
+pub(crate) struct Watches {
+    watcher: Option<notify::RecommendedWatcher>,
+    /// Paths reported as changed by the watcher.
+    changes: Mutex<Option<mpsc::Receiver<PathBuf>>>,
+    /// Paths that failed to reload, retried by the next [`Registry::poll_changes`].
+    retry: HashSet<PathBuf>,
+    files: Vec<WatchedFile>,
+    subscribers: Vec<mpsc::Sender<AssetEvent>>,
+}
+
+/// A watched container and the bundles that were loaded from it.
+struct WatchedFile {
+    path: PathBuf,
+    bundles: Vec<String>,
+}
+
+impl Default for Watches {
+    fn default() -> Self {
+        Watches {
+            watcher: None,
+            changes: Mutex::new(None),
+            retry: HashSet::new(),
+            files: Vec::new(),
+            subscribers: Vec::new(),
+        }
+    }
+}
+
+impl Registry {
+    /// Loads a compiled container and reloads it whenever it changes on disk.
+    ///
+    /// With the `ron` feature, files with the `.ron` extension are loaded as RON asset files,
+    /// see `RegistryBuilder::load_ron_file`.
+    ///
+    /// Bundles of the container that are already loaded are replaced. Changes are applied by
+    /// [`Registry::poll_changes`].
+    pub fn watch(&mut self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref().canonicalize()?;
+        let directory = path
+            .parent()
+            .ok_or_else(|| Error::Io(std::io::Error::other("Watched path has no parent")))?
+            .to_path_buf();
+
+        let watches = &mut self.watches;
+        if watches.watcher.is_none() {
+            let (sender, receiver) = mpsc::channel();
+            let watcher =
+                notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                    if let Ok(event) = event
+                        && (event.kind.is_create() || event.kind.is_modify())
+                    {
+                        for path in event.paths {
+                            let _ = sender.send(path);
+                        }
+                    }
+                })
+                .map_err(watch_error)?;
+            watches.watcher = Some(watcher);
+            *watches.changes.get_mut().unwrap() = Some(receiver);
+        }
+        // Editors often replace files instead of writing them, so the directory is watched.
+        if let Some(watcher) = watches.watcher.as_mut() {
+            watcher
+                .watch(&directory, notify::RecursiveMode::NonRecursive)
+                .map_err(watch_error)?;
+        }
+
+        let binary = self.read_container(&path)?;
+        let (bundles, events) = self.reload_bundles(&binary, &[])?;
+        self.watches.files.push(WatchedFile { path, bundles });
+        self.notify(&events);
+        Ok(())
+    }
+
+    /// Reloads the watched containers that changed since the last call.
+    ///
+    /// Each container is replaced atomically: if it cannot be loaded, for example because it
+    /// is only partially written, its previous assets are kept and it is retried by the next
+    /// call. The changes are returned and sent to all subscribers.
+    ///
+    /// The other changed containers are still reloaded when one fails. Their changes are sent
+    /// to all subscribers before the first error is returned, so subscribers see every change
+    /// even when this returns an error.
+    pub fn poll_changes(&mut self) -> Result<Vec<AssetEvent>, Error> {
+        let mut changed = std::mem::take(&mut self.watches.retry);
+        match self.watches.changes.get_mut().unwrap() {
+            Some(receiver) => changed.extend(receiver.try_iter()),
+            None => return Ok(Vec::new()),
+        }
+
+        let mut events = Vec::new();
+        let mut error = None;
+        for index in 0..self.watches.files.len() {
+            let path = self.watches.files[index].path.clone();
+            if !changed.contains(&path) {
+                continue;
+            }
+            match self.reload_file(index) {
+                Ok(changes) => events.extend(changes),
+                Err(failure) => {
+                    self.watches.retry.insert(path);
+                    error.get_or_insert(failure);
+                }
+            }
+        }
+
+        self.notify(&events);
+        match error {
+            Some(error) => Err(error),
+            None => Ok(events),
+        }
+    }
+
+    /// Reloads the watched file at `index`, returning the changes.
+    fn reload_file(&mut self, index: usize) -> Result<Vec<AssetEvent>, Error> {
+        let file = &self.watches.files[index];
+        let binary = self.read_container(&file.path)?;
+        let previous = file.bundles.clone();
+        let (bundles, events) = self.reload_bundles(&binary, &previous)?;
+        self.watches.files[index].bundles = bundles;
+        Ok(events)
+    }
+
+    /// Returns a receiver for the changes applied by [`Registry::watch`] and
+    /// [`Registry::poll_changes`].
+    pub fn subscribe(&mut self) -> mpsc::Receiver<AssetEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.watches.subscribers.push(sender);
+        receiver
+    }
+
+    /// Reads a watched file as a compiled container, compiling RON asset files first.
+    fn read_container(&self, path: &Path) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "ron")]
+        if path.extension().is_some_and(|extension| extension == "ron") {
+            let source = std::fs::read_to_string(path)?;
+            return self.compile_ron(&source, path.parent().unwrap_or(Path::new("")));
+        }
+        Ok(std::fs::read(path)?)
+    }
+
+    /// Sends events to all subscribers, dropping those whose receiver is gone.
+    fn notify(&mut self, events: &[AssetEvent]) {
+        if events.is_empty() {
+            return;
+        }
+        self.watches.subscribers.retain(|subscriber| {
+            events
+                .iter()
+                .all(|event| subscriber.send(event.clone()).is_ok())
+        });
+    }
+}
+
+fn watch_error(error: notify::Error) -> Error {
+    Error::Io(std::io::Error::other(error))
+}
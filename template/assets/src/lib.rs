@@ -1,13 +1,15 @@
 use rasset::prelude::*;
 
 asset_def! {
+    #[asset(id = "sprite")]
     Sprite: {
         width: i64,
         height: i64,
         texture: Vec<u8>,
     },
+    #[asset(id = "sprites")]
     Sprites: {
-        sprites: Vec<String>,
+        sprites: Vec<AssetRef<Sprite>>,
     }
 }
 
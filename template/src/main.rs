@@ -13,11 +13,11 @@ pub fn main() {
     let sprites = registry
         .get_asset::<assets::Sprites>("Sprites")
         .expect("Failed to get PlayerSprite asset");
-    for sprite_name in &sprites.sprites {
+    for sprite in &sprites.sprites {
         println!(
             "Sprite: {:?}",
-            registry
-                .get_asset::<assets::Sprite>(sprite_name)
+            sprite
+                .resolve(&registry)
                 .expect("Failed to get sprite asset")
         );
     }